@@ -1,17 +1,49 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Signature};
+use syn::{Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Signature};
+
+/// Ordering of the init fn registering the routes, e.g.
+/// `#[controller(order = 10, after = "OtherController")]`.
+#[derive(Default)]
+pub struct ControllerArgs {
+    order: Option<Expr>,
+    before: Vec<LitStr>,
+    after: Vec<LitStr>,
+}
+
+impl ControllerArgs {
+    pub fn parse(args: proc_macro::TokenStream) -> syn::Result<ControllerArgs> {
+        let mut controller_args = ControllerArgs::default();
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("order") {
+                controller_args.order = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("before") {
+                controller_args.before.push(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("after") {
+                controller_args.after.push(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported controller property, expected `order`, `before` or `after`"))
+            }
+        });
+        syn::parse::Parser::parse(parser, args)?;
+        Ok(controller_args)
+    }
+}
 
-pub fn generate_init_fn_for_controller(item_impl: ItemImpl) -> TokenStream {
-    let vine_setup = quote!(vine::vine_core::context::auto_register_context::SETUP);
-    let vine_context = quote!(vine::vine_core::context::context::Context);
-    let vine_error = quote!(vine::vine_core::core::Error);
+pub fn generate_init_fn_for_controller(args: ControllerArgs, item_impl: ItemImpl) -> TokenStream {
+    let vine_setup = quote!(vine::vine_core::context::auto_register_context::NAMED_SETUP);
+    let vine_setup_ty = quote!(vine::vine_core::context::auto_register_context::Setup);
     let vine_web = quote!(vine::vine_axum::Web);
 
     let ItemImpl { self_ty, items, .. } = &item_impl;
+    let ControllerArgs { order, before, after } = &args;
+    let order = order.iter();
 
     let routes: Vec<_>  = items.iter()
-        .flat_map(|impl_item| handler_to_method_router(impl_item))
+        .flat_map(handler_to_method_router)
         .map(|route| quote!(web.add_route(#route);) )
         .collect();
 
@@ -19,17 +51,32 @@ pub fn generate_init_fn_for_controller(item_impl: ItemImpl) -> TokenStream {
     let controller = LitStr::new(ty_name.as_str(), Span::call_site());
     let setup_ident = Ident::new(format!("SETUP_INIT_FN_{}_CONTROLLER", ty_name.to_uppercase()).as_str(), Span::call_site());
 
-    quote!(
-        #[vine::distributed_slice(#vine_setup)]
-        pub static #setup_ident: fn(&#vine_context) -> Result<(), #vine_error> = |ctx| {
-            ctx.add_init_fn(#controller, std::sync::Arc::new(|ctx| {
-                let web = ctx.get_primary_bean::<#vine_web>()?;
-                let controller = ctx.get_primary_bean::<#self_ty>()?;
+    let register_as = quote!(vine::vine_core::context::registration::RegisterAs<vine::vine_axum::Routes>);
 
-                #(#routes)*
+    quote!(
+        impl #register_as for #self_ty {
+            fn register_as(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                ctx.add_init_fn_def(vine::vine_core::core::init_fn_def::InitFnDef::builder()
+                    .name(#controller)
+                    #(.order(#order))*
+                    #(.before(#before))*
+                    #(.after(#after))*
+                    .init_fn(std::sync::Arc::new(|ctx| {
+                        let web = ctx.get_primary_bean::<#vine_web>()?;
+                        let controller = ctx.get_primary_bean::<#self_ty>()?;
+
+                        #(#routes)*
+
+                        Ok(())
+                    }))
+                    .build())
+            }
+        }
 
-                Ok(())
-            }))
+        #[vine::distributed_slice(#vine_setup)]
+        pub static #setup_ident: #vine_setup_ty = #vine_setup_ty {
+            name: #controller,
+            setup: <#self_ty as #register_as>::register_as,
         };
 
         #item_impl
@@ -86,6 +133,7 @@ mod tests {
 
     #[test]
     fn test_to_routes() {
+        let args = ControllerArgs { order: Some(parse_quote!(10)), before: vec![], after: vec![parse_quote!("OtherController")] };
         let item_impl: ItemImpl = parse_quote! {
             impl TestController {
                 #[get("/test")]
@@ -100,12 +148,14 @@ mod tests {
             }
         };
 
-        let token_stream = generate_init_fn_for_controller(item_impl);
+        let token_stream = generate_init_fn_for_controller(args, item_impl);
         let syntax_tree: syn::File = parse_quote! {
             #token_stream
         };
         let unparse = prettyplease::unparse(&syntax_tree);
        
         println!("{}", &unparse);
+        assert!(unparse.contains(".order(10)"));
+        assert!(unparse.contains(".after(\"OtherController\")"));
     }
 }
\ No newline at end of file
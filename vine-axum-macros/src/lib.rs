@@ -2,7 +2,7 @@ mod controller;
 
 use proc_macro::TokenStream;
 use syn::Item;
-use crate::controller::generate_init_fn_for_controller;
+use crate::controller::{ControllerArgs, generate_init_fn_for_controller};
 
 /// Generates a controller implementation with initialization functions for web routing.
 /// 
@@ -13,7 +13,7 @@ use crate::controller::generate_init_fn_for_controller;
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,ignore
 /// #[controller]
 /// impl MyController {
 ///     #[get("/hello")]
@@ -22,11 +22,18 @@ use crate::controller::generate_init_fn_for_controller;
 ///     }
 /// }
 /// ```
+///
+/// The routes are registered by an init fn named after the controller type, ordered with
+/// `#[controller(order = 10, before = "OtherController", after = "...")]`.
 #[proc_macro_attribute]
-pub fn controller(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn controller(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match ControllerArgs::parse(attr) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
     let item = syn::parse_macro_input!(input as Item);
     match item {
-        Item::Impl(item_impl) => generate_init_fn_for_controller(item_impl).into(),
+        Item::Impl(item_impl) => generate_init_fn_for_controller(args, item_impl).into(),
         _ => panic!("unsupported...")
     }
 }
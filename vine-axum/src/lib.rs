@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::sync::Arc;
 use async_trait::async_trait;
 use axum::Router;
@@ -6,12 +7,27 @@ use dashmap::DashMap;
 use linkme::distributed_slice;
 use log::{debug, trace};
 use vine_core::config::PropertyResolver;
-use vine_core::context::context::Context;
 use vine_core::core::Error;
-use vine_core::context::auto_register_context::SETUP;
+use vine_core::context::auto_register_context::{NAMED_SETUP, Setup};
+use vine_core::context::context::Context;
+use vine_core::context::module::Module;
+use vine_core::context::registration::Register;
 use vine_core::core::bean_def::BeanDef;
+use vine_core::core::dependency::Dependency;
+use vine_core::core::runner::Runner;
+use vine_core::core::shutdown::Shutdown;
 use vine_core::core::ty::Type;
 
+/// Marker of the routes of a `#[controller]`, registered explicitly with
+/// `App::builder().register_as::<MyController, Routes>()`.
+pub enum Routes {}
+
+/// The `web` bean, to compose an App without the auto-registered setups, e.g.
+/// `App::builder().module(vine_axum::module())`.
+pub fn module() -> Module {
+    Module::named("web").register::<Web>()
+}
+
 pub struct Web {
     host: String,
     port: String,
@@ -26,11 +42,8 @@ impl Web {
             self.routes.insert(path, vec![method_router]);
         }
     }
-}
 
-#[async_trait]
-impl vine_core::core::runner::Runner for Web {
-    async fn run(&self) -> Result<(), Error> {
+    async fn serve<F: Future<Output = ()> + Send + 'static>(&self, signal: F) -> Result<(), Error> {
 
         trace!("register endpoints");
         let router = {
@@ -49,30 +62,53 @@ impl vine_core::core::runner::Runner for Web {
         let addr = format!("{}:{}", &self.host, &self.port);
 
         trace!("tokio::net::TcpListener bind address: {}", &addr);
-        let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+        let listener = tokio::net::TcpListener::bind(&addr).await
+            .map_err(|e| Error::RunnerFailed { runner: self.name().to_string(), source: Box::new(e) })?;
 
         debug!("axum::serve: {}", &addr);
-        axum::serve(listener, router).await.unwrap();
-        Ok(())
+        axum::serve(listener, router)
+            .with_graceful_shutdown(signal)
+            .await
+            .map_err(|e| Error::RunnerFailed { runner: self.name().to_string(), source: Box::new(e) })
+    }
+}
+
+#[async_trait]
+impl Runner for Web {
+    async fn run(&self) -> Result<(), Error> {
+        self.serve(std::future::pending()).await
+    }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to complete.
+    async fn run_until_shutdown(&self, shutdown: Shutdown) -> Result<(), Error> {
+        self.serve(async move {
+            shutdown.wait().await;
+            debug!("axum graceful shutdown");
+        }).await
     }
 }
 
-#[distributed_slice(SETUP)]
-pub static SETUP_WEB: fn(&Context) -> Result<(), Error> = |ctx| {
-    trace!("Setup axum based web Bean");
-    let ty = Type::of::<Web>();
-    ty.add_downcast::<Web>(|b| Ok(Arc::downcast::<Web>(b)?));
-    ty.add_downcast::<dyn vine_core::core::runner::Runner + Send + Sync>(|b| Ok(Arc::downcast::<Web>(b)?));
+impl Register for Web {
+    fn register(ctx: &Context) -> Result<(), Error> {
+        trace!("Setup axum based web Bean");
+        let ty = Type::of::<Web>();
+        ty.add_downcast::<Web>(Arc::downcast::<Web>);
+        ty.add_downcast::<dyn Runner + Send + Sync>(|b| Ok(Arc::downcast::<Web>(b)?));
+
+        ctx.register(BeanDef::builder()
+            .name("web")
+            .ty(ty)
+            .dependency(Dependency::primary::<dyn PropertyResolver + Send + Sync>("config"))
+            .get(Arc::new(|ctx| {
+                let config = ctx.get_primary_bean::<dyn PropertyResolver + Send + Sync>()?;
+                let host = config.compute_template_value("${server.host:0.0.0.0}")?;
+                let port = config.compute_template_value("${server.port:3000}")?;
+                let web = Arc::new(Web { host, port, routes: Default::default() });
+                Ok(web)
+            }))
+            .build())
+    }
+}
 
-    ctx.register(BeanDef::builder()
-        .name("web")
-        .ty(ty)
-        .get(Arc::new(|ctx| {
-            let config = ctx.get_primary_bean::<dyn PropertyResolver + Send + Sync>()?;
-            let host = config.compute_template_value("${server.host:0.0.0.0}")?;
-            let port = config.compute_template_value("${server.port:3000}")?;
-            let web = Arc::new(Web { host, port, routes: Default::default() });
-            Ok(web)
-        }))
-        .build())
-};
\ No newline at end of file
+#[distributed_slice(NAMED_SETUP)]
+pub static SETUP_WEB: Setup = Setup { name: "web", setup: Web::register };
\ No newline at end of file
@@ -1,73 +1,492 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use log::{debug, info, warn};
+use log::{debug, info, trace, warn};
+use tokio::task::JoinSet;
 
+use crate::config::{get_config_context_with_properties, get_inline_config_context, PropertyResolver};
+use crate::context::auto_register_context::SetupFn;
 use crate::context::context::Context;
+use crate::context::module::Module;
+use crate::context::override_policy::OverridePolicy;
+use crate::context::registration::{Register, RegisterAs};
 use crate::core::Error;
+use crate::core::bean_def::BeanDef;
+use crate::core::event::{ApplicationEventPublisher, ApplicationStarted, ApplicationStopping, ContextInitialized};
 use crate::core::runner::Runner;
+use crate::core::shutdown::{self, ShutdownTrigger};
+use crate::core::ty::Type;
+use crate::logger::init_logger;
+
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct App {
     context: Arc<Context>,
+    shutdown: ShutdownTrigger,
+}
+
+/// Composes an [`App`] from its config, [`Module`]s and beans, e.g.
+/// `App::builder().config_file("app.yaml").module(Module::auto_registered()).build()`.
+///
+/// The config Context is added first, then one Context per module in the given order and finally
+/// the `application` Context holding the registered beans.
+pub struct AppBuilder {
+    config_files: Vec<String>,
+    properties: Vec<(String, String)>,
+    environment: bool,
+    init_logger: bool,
+    override_policy: Option<OverridePolicy>,
+    modules: Vec<Module>,
+    registrations: Vec<SetupFn>,
+    bean_defs: Vec<BeanDef>,
 }
 
 impl Default for App {
     fn default() -> Self {
         App {
             context: Arc::new(Context::new("root")),
+            shutdown: ShutdownTrigger::default(),
         }
     }
 }
 
 impl App {
+    pub fn builder() -> AppBuilder {
+        AppBuilder {
+            config_files: Vec::new(),
+            properties: Vec::new(),
+            environment: true,
+            init_logger: true,
+            override_policy: None,
+            modules: Vec::new(),
+            registrations: Vec::new(),
+            bean_defs: Vec::new(),
+        }
+    }
+
     pub fn get_context(&self) -> &Context {
         &self.context
     }
 
-    pub fn add_context(&self, context: Context) {
-        self.context.add_context(context);
+    /// Adds a context to the App, failing only when its name, beans or init fns are already in
+    /// use and the [`OverridePolicy`] of the App is [`OverridePolicy::Reject`].
+    pub fn add_context(&self, context: Context) -> Result<(), Error> {
+        self.context.add_context(context)
     }
 
+    /// Requests a graceful shutdown, as if SIGINT or SIGTERM had been received.
+    pub fn shutdown(&self) {
+        self.shutdown.shutdown();
+    }
+
+    /// Starts every Runner and waits until all of them have finished.
+    ///
+    /// On SIGINT, SIGTERM or [`App::shutdown`] the Runners are asked to stop and given
+    /// `vine.shutdown.timeout` (default `30s`) to drain, the remaining ones are aborted. A second
+    /// SIGINT or SIGTERM within that window aborts them immediately.
+    /// Beans are destroyed afterwards.
+    ///
+    /// Publishes [`ContextInitialized`], [`ApplicationStarted`] and [`ApplicationStopping`]
+    /// along the way.
     pub async fn exec(&self) -> Result<(), Error> {
         let timer = Instant::now();
         
         info!("starting application");
+        let publisher = ApplicationEventPublisher::new(&self.context);
+        // singletons come first, so that init fns find the async ones already created
+        self.context.instantiate_singletons().await?;
         self.context.init_contexts()?;
+        publisher.publish_async(ContextInitialized).await?;
 
         let mut runners = self.context.get_beans::<dyn Runner + Send + Sync>()?;
         debug!("starting {} runners", runners.len());
 
-        let mut handles = Vec::new();
+        let shutdown_timeout = self.get_shutdown_timeout()?;
+        let mut tasks = JoinSet::new();
+        let mut names = HashMap::new();
         while let Some(r) = runners.pop() {
             let runner = r.clone();
-            let name = runner.name().to_string();
-            
+            let shutdown = self.shutdown.subscribe();
+
             debug!("starting runner {}", r.name());
-            handles.push(tokio::spawn(async move {
-                let result = runner.run().await;
-                (name, result)
-            }));
+            let handle = tasks.spawn(async move { runner.run_until_shutdown(shutdown).await });
+            names.insert(handle.id(), r.name().to_string());
             debug!("runner {} has been started in {} micros", r.name(), timer.elapsed().as_micros());
         }
         info!("started in {} micros", timer.elapsed().as_micros());
+        publisher.publish_async(ApplicationStarted).await?;
 
         let mut errors = Vec::new();
-        while let Some(runner_result) = handles.pop() {
-            let (name, result) = runner_result.await.map_err(|_| {
-               Error::from("failed to start runner")
-            })?;
-
-            if let Err(error) = result {
-                warn!("runner {} has been finished with error: {}", &name, &error);
-                errors.push(error);
+        let shutdown = self.shutdown.subscribe();
+        let signal = shutdown::wait_for_signal();
+        tokio::pin!(signal);
+        let mut deadline = None;
+        let mut aborted = false;
+        loop {
+            tokio::select! {
+                next = tasks.join_next_with_id() => {
+                    let error = match next {
+                        None => break,
+                        Some(Ok((_, Ok(())))) => continue,
+                        Some(Ok((id, Err(error)))) => Error::RunnerFailed { runner: names[&id].clone(), source: Box::new(error) },
+                        Some(Err(error)) => Error::RunnerFailed { runner: names[&error.id()].clone(), source: Box::new(error) },
+                    };
+
+                    warn!("{}", &error);
+                    errors.push(error);
+                },
+                _ = &mut signal => {
+                    if deadline.is_some() {
+                        warn!("second signal received, aborting {} runners", tasks.len());
+                        aborted = true;
+                        deadline = Some(tokio::time::Instant::now());
+                    }
+                    self.shutdown.shutdown();
+                    signal.set(shutdown::wait_for_signal());
+                },
+                _ = shutdown.wait(), if deadline.is_none() => {
+                    info!("shutting down, waiting up to {:?} for {} runners", shutdown_timeout, tasks.len());
+                    deadline = Some(tokio::time::Instant::now() + shutdown_timeout);
+                    Self::publish_stopping(&publisher).await;
+                },
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)), if deadline.is_some() => {
+                    tasks.abort_all();
+                    while let Some(next) = tasks.join_next_with_id().await {
+                        let id = match next {
+                            Ok((id, _)) => id,
+                            Err(error) => error.id(),
+                        };
+                        let source = match aborted {
+                            true => "aborted by a second signal".into(),
+                            false => format!("did not stop within {:?}", shutdown_timeout).into(),
+                        };
+                        let error = Error::RunnerFailed { runner: names[&id].clone(), source };
+                        warn!("{}", &error);
+                        errors.push(error);
+                    }
+                    break;
+                },
             }
         }
 
+        if deadline.is_none() {
+            Self::publish_stopping(&publisher).await;
+        }
+
+        debug!("destroying beans");
+        if let Err(error) = self.context.destroy().await {
+            warn!("failed to destroy beans: {}", &error);
+            errors.push(error);
+        }
+
         info!("application finished {} micros", timer.elapsed().as_micros());
-        if errors.is_empty() {
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.pop().unwrap()),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    async fn publish_stopping(publisher: &ApplicationEventPublisher) {
+        if let Err(error) = publisher.publish_async(ApplicationStopping).await {
+            warn!("failed to publish ApplicationStopping: {}", &error);
+        }
+    }
+
+    fn get_shutdown_timeout(&self) -> Result<Duration, Error> {
+        let Ok(config) = self.context.get_primary_bean::<dyn PropertyResolver + Send + Sync>() else {
+            return Ok(DEFAULT_SHUTDOWN_TIMEOUT);
+        };
+
+        let value = config.compute_template_value("${vine.shutdown.timeout:}")?;
+        match value.trim() {
+            "" => Ok(DEFAULT_SHUTDOWN_TIMEOUT),
+            value => parse_duration(value),
+        }
+    }
+}
+
+impl AppBuilder {
+    pub fn config_files(mut self, config_files: Vec<String>) -> Self {
+        self.config_files.extend(config_files);
+        self
+    }
+
+    pub fn config_file(mut self, config_file: &str) -> Self {
+        self.config_files.push(config_file.to_string());
+        self
+    }
+
+    /// Sets an inline property, taking precedence over every other config source.
+    pub fn property(mut self, key: &str, value: &str) -> Self {
+        self.properties.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Whether `APP_` prefixed environment variables and `--key=value` command line arguments are
+    /// part of the config, `true` by default.
+    pub fn environment(mut self, environment: bool) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Whether the logger is initialized from the config, `true` by default.
+    pub fn init_logger(mut self, init_logger: bool) -> Self {
+        self.init_logger = init_logger;
+        self
+    }
+
+    /// Overrides the policy otherwise read from `vine.main.allow-bean-definition-overriding`.
+    pub fn override_policy(mut self, override_policy: OverridePolicy) -> Self {
+        self.override_policy = Some(override_policy);
+        self
+    }
+
+    pub fn module(mut self, module: Module) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    /// Registers the beans of `T`, e.g. declared with `#[derive(Bean)]`, without relying on the
+    /// `SETUP` distributed slices.
+    pub fn register<T: Register>(mut self) -> Self {
+        self.registrations.push(T::register);
+        self
+    }
+
+    /// Registers `T` as an implementation of `A`, e.g. declared with `#[injectable]`.
+    pub fn register_as<T: RegisterAs<A>, A: ?Sized>(mut self) -> Self {
+        self.registrations.push(T::register_as);
+        self
+    }
+
+    pub fn register_bean_def(mut self, bean_def: impl Into<BeanDef>) -> Self {
+        self.bean_defs.push(bean_def.into());
+        self
+    }
+
+    /// Registers an existing instance as the `name` bean, downcastable to `T`.
+    pub fn register_instance<T: Send + Sync + 'static>(self, name: &str, bean: Arc<T>) -> Self {
+        let ty = Type::of::<T>();
+        ty.add_downcast::<T>(Arc::downcast::<T>);
+        self.register_bean_def(BeanDef::builder()
+            .name(name)
+            .ty(ty)
+            .get(Arc::new(move |_| Ok(bean.clone())))
+            .build())
+    }
+
+    pub fn build(self) -> Result<App, Error> {
+        let config = match self.environment {
+            true => get_config_context_with_properties(self.config_files, self.properties)?,
+            false => get_inline_config_context(self.config_files, self.properties)?,
+        };
+        if self.init_logger {
+            init_logger(&config)?;
+        }
+
+        let app = App::default();
+        trace!("setup - adding {} to App", &config);
+        app.add_context(config)?;
+
+        let override_policy = match self.override_policy {
+            Some(override_policy) => Some(override_policy),
+            None => {
+                let config = app.context.get_bean::<dyn PropertyResolver + Send + Sync>("config")?;
+                OverridePolicy::from_config(config.as_ref())
+            }
+        };
+        if let Some(override_policy) = override_policy {
+            app.context.set_override_policy(override_policy);
+        }
+
+        for module in self.modules {
+            let context = module.into_context()?;
+            trace!("setup - adding {} to App", &context);
+            app.add_context(context)?;
+        }
+
+        if !self.registrations.is_empty() || !self.bean_defs.is_empty() {
+            let context = Context::new("application");
+            for register in self.registrations {
+                register(&context)?;
+            }
+            for bean_def in self.bean_defs {
+                context.register(bean_def)?;
+            }
+            trace!("setup - adding {} to App", &context);
+            app.add_context(context)?;
+        }
+
+        Ok(app)
+    }
+}
+
+/// Parses durations like `30s`, `500ms`, `1m` or `30` (seconds).
+fn parse_duration(value: &str) -> Result<Duration, Error> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount = amount.parse::<u64>()
+        .map_err(|e| Error::PropertyParse { value: value.to_string(), ty: "Duration", source: Box::new(e) })?;
+
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "" | "s" => Ok(Duration::from_secs(amount)),
+        "m" => Ok(Duration::from_secs(amount * 60)),
+        unit => Err(Error::PropertyParse { value: value.to_string(), ty: "Duration", source: format!("unknown unit {:?}", unit).into() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use crate::app::{App, parse_duration};
+    use crate::context::context::Context;
+    use crate::context::module::Module;
+    use crate::context::override_policy::OverridePolicy;
+    use crate::core::{DynBean, Error};
+    use crate::core::bean_def::BeanDef;
+    use crate::core::runner::Runner;
+    use crate::core::shutdown::Shutdown;
+    use crate::core::ty::Type;
+
+    struct DrainingRunner { drained: AtomicBool }
+
+    #[async_trait]
+    impl Runner for DrainingRunner {
+        async fn run(&self) -> Result<(), Error> {
+            std::future::pending().await
+        }
+
+        async fn run_until_shutdown(&self, shutdown: Shutdown) -> Result<(), Error> {
+            shutdown.wait().await;
+            self.drained.store(true, Ordering::SeqCst);
             Ok(())
-        } else {
-            Err(Error::from(errors.join("\n")))
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_parse_durations() -> Result<(), Error> {
+        assert_eq!(parse_duration("30")?, Duration::from_secs(30));
+        assert_eq!(parse_duration("5s")?, Duration::from_secs(5));
+        assert_eq!(parse_duration("500ms")?, Duration::from_millis(500));
+        assert_eq!(parse_duration("2m")?, Duration::from_secs(120));
+        assert!(matches!(parse_duration("1h"), Err(Error::PropertyParse { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn should_build_app_from_modules_and_beans() -> Result<(), Error> {
+        let module = Module::new("greetings", |ctx| {
+            ctx.register(BeanDef::builder()
+                .name("greeting")
+                .ty(Type::of::<String>())
+                .get(Arc::new(|_| Ok(Arc::new("from module".to_string()))))
+                .build())
+        });
+        let app = App::builder()
+            .environment(false)
+            .init_logger(false)
+            .property("vine.main.allow-bean-definition-overriding", "true")
+            .module(module)
+            .register_instance("greeting", Arc::new("registered".to_string()))
+            .register_instance("count", Arc::new(3_u32))
+            .build()?;
+
+        let context = app.get_context();
+        assert_eq!(context.override_policy(), OverridePolicy::Replace);
+        assert_eq!(*context.get_bean::<String>("greeting")?, "registered");
+        assert_eq!(*context.get_bean::<u32>("count")?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_overriding_beans_only_when_disallowed() -> Result<(), Error> {
+        let build = |allowed: Option<&str>| {
+            let mut builder = App::builder()
+                .environment(false)
+                .init_logger(false)
+                .register_instance("greeting", Arc::new("registered".to_string()))
+                .module(Module::new("greetings", |ctx| {
+                    ctx.register(BeanDef::builder()
+                        .name("greeting")
+                        .ty(Type::of::<String>())
+                        .get(Arc::new(|_| Ok(Arc::new("from module".to_string()))))
+                        .build())
+                }));
+            if let Some(allowed) = allowed {
+                builder = builder.property("vine.main.allow-bean-definition-overriding", allowed);
+            }
+            builder.build()
+        };
+
+        let app = build(None)?;
+        assert_eq!(app.get_context().override_policy(), OverridePolicy::Replace);
+        assert_eq!(*app.get_context().get_bean::<String>("greeting")?, "registered");
+        assert!(matches!(build(Some("false")), Err(Error::DuplicateBean { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_create_async_singletons_before_init_fns() -> Result<(), Error> {
+        let app = App::default();
+        let context = Context::new("test");
+        let ty = Type::of::<String>();
+        ty.add_downcast::<String>(Arc::downcast::<String>);
+        context.register(BeanDef::builder()
+            .name("asyncGreeting")
+            .ty(ty)
+            .get_async(|_| async {
+                tokio::task::yield_now().await;
+                let bean: DynBean = Arc::new("hello".to_string());
+                Ok(bean)
+            })
+            .build())?;
+
+        let greeted = Arc::new(AtomicBool::new(false));
+        let init_greeted = greeted.clone();
+        context.add_init_fn("greet", Arc::new(move |ctx| {
+            init_greeted.store(*ctx.get_bean::<String>("asyncGreeting")? == "hello", Ordering::SeqCst);
+            Ok(())
+        }))?;
+        app.add_context(context)?;
+
+        app.exec().await?;
+        assert!(greeted.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_drain_runners_on_shutdown() -> Result<(), Error> {
+        let app = Arc::new(App::default());
+        let runner = Arc::new(DrainingRunner { drained: AtomicBool::new(false) });
+
+        let ty = Type::of::<DrainingRunner>();
+        ty.add_downcast::<dyn Runner + Send + Sync>(|b| Ok(Arc::downcast::<DrainingRunner>(b)?));
+        let context = Context::new("test");
+        let bean = runner.clone();
+        context.register(BeanDef::builder()
+            .name("drainingRunner")
+            .ty(ty)
+            .get(Arc::new(move |_| Ok(bean.clone())))
+            .build())?;
+        app.add_context(context)?;
+
+        let exec = tokio::spawn({
+            let app = app.clone();
+            async move { app.exec().await }
+        });
+        tokio::task::yield_now().await;
+        app.shutdown();
+
+        exec.await.unwrap()?;
+        assert!(runner.drained.load(Ordering::SeqCst));
+        Ok(())
+    }
+}
@@ -3,7 +3,8 @@ mod property_resolver;
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
-use config::{Config, Environment, File};
+use config::{Config, ConfigBuilder, Environment, File};
+use config::builder::DefaultState;
 use crate::context::context::Context;
 use crate::core::bean_def::BeanDef;
 use crate::core::Error;
@@ -11,26 +12,98 @@ use crate::core::ty::Type;
 
 pub use property_resolver::PropertyResolver as PropertyResolver;
 
+/// Property holding the comma separated list of active profiles.
+pub const ACTIVE_PROFILES_PROPERTY: &str = "vine.profiles.active";
+
+/// Builds the config Context from the given files, `APP_` prefixed environment variables and
+/// `--key=value` command line arguments, in increasing precedence.
+///
+/// For every active profile (see [`ACTIVE_PROFILES_PROPERTY`]) the profile specific variant of
+/// each file, e.g. `app-dev.yaml` for `app.yaml`, is loaded on top of the base files.
 pub fn get_config_context(config_files: Vec<String>) -> Result<Context, Error> {
+    get_config_context_with_properties(config_files, Vec::new())
+}
+
+/// Same as [`get_config_context`], with inline properties taking precedence over every source.
+pub fn get_config_context_with_properties(config_files: Vec<String>, properties: Vec<(String, String)>) -> Result<Context, Error> {
+    build_config_context(&config_files, |config_builder| add_properties(add_overrides(config_builder, app_environment())?, &properties))
+}
+
+/// Builds the config Context from the given files and inline properties only, the latter taking
+/// precedence. Environment variables and command line arguments are ignored, which keeps the
+/// config of tests independent of the process they run in.
+pub fn get_inline_config_context(config_files: Vec<String>, properties: Vec<(String, String)>) -> Result<Context, Error> {
+    build_config_context(&config_files, |config_builder| add_properties(config_builder, &properties))
+}
+
+fn build_config_context(
+    config_files: &[String],
+    add_overrides: impl Fn(ConfigBuilder<DefaultState>) -> Result<ConfigBuilder<DefaultState>, Error>,
+) -> Result<Context, Error> {
     let config_context = Context::new("config");
 
     let ty = Type::of::<Config>();
-    ty.add_downcast::<Config>(|b| Ok(Arc::downcast::<Config>(b)?));
+    ty.add_downcast::<Config>(Arc::downcast::<Config>);
     ty.add_downcast::<dyn PropertyResolver + Send + Sync>(|b| Ok(Arc::downcast::<Config>(b)?));
 
-    let mut config_builder = Config::builder();
+    // the active profiles may be set in any source, so they are resolved before loading profile files
+    let base_config = add_overrides(add_config_files(Config::builder(), config_files))?
+        .build()
+        .map_err(Error::Config)?;
+    let profiles = get_active_profiles(&base_config);
+    log::debug!("Active profiles: {:?}", &profiles);
+
+    let mut config_builder = add_config_files(Config::builder(), config_files);
+    config_builder = add_config_files(config_builder, &get_profile_config_files(config_files, &profiles));
+    config_builder = add_overrides(config_builder)?;
+
+    let config = Arc::new(config_builder.build().map_err(Error::Config)?);
+    config_context.register(BeanDef::builder()
+        .name("config")
+        .ty(ty)
+        .get(Arc::new(move |_| { Ok(config.clone()) }))
+        .build())?;
+
+    Ok(config_context)
+}
+
+/// Returns the active profiles, in the order they have been declared.
+pub fn get_active_profiles(resolver: &dyn PropertyResolver) -> Vec<String> {
+    resolver.get_list(ACTIVE_PROFILES_PROPERTY).unwrap_or_default()
+}
+
+fn get_profile_config_files(config_files: &[String], profiles: &[String]) -> Vec<String> {
+    profiles.iter()
+        .flat_map(|profile| config_files.iter().map(move |config_file| {
+            let path = Path::new(config_file);
+            let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            let file_name = match path.extension().and_then(|extension| extension.to_str()) {
+                None => format!("{}-{}", stem, profile),
+                Some(extension) => format!("{}-{}.{}", stem, profile, extension),
+            };
+            path.with_file_name(file_name).to_string_lossy().to_string()
+        }))
+        .collect()
+}
+
+fn add_config_files(mut config_builder: ConfigBuilder<DefaultState>, config_files: &[String]) -> ConfigBuilder<DefaultState> {
     for config_file in config_files {
         if Path::new(config_file.as_str()).exists() {
             log::debug!("Loading config file: {}", config_file);
-            config_builder = config_builder.add_source(File::with_name(&config_file))
+            config_builder = config_builder.add_source(File::with_name(config_file))
         }
     }
+    config_builder
+}
+
+/// Environment variables with APP prefix (e.g., APP_server_port=8080)
+fn app_environment() -> Environment {
+    Environment::with_prefix("APP").prefix_separator("_").separator(".").try_parsing(true)
+}
 
-    // Use environment variables with APP prefix (e.g., APP_server_port=8080)
+fn add_overrides(mut config_builder: ConfigBuilder<DefaultState>, environment: Environment) -> Result<ConfigBuilder<DefaultState>, Error> {
     log::debug!("Loading environment variables with APP prefix");
-    config_builder = config_builder.add_source(
-        Environment::with_prefix("APP").prefix_separator("_").separator(".").try_parsing(true)
-    );
+    config_builder = config_builder.add_source(environment);
 
     // Parse -- style command line arguments (e.g., --server.port=8080)
     log::debug!("Parsing -- style command line arguments");
@@ -45,25 +118,23 @@ pub fn get_config_context(config_files: Vec<String>) -> Result<Context, Error> {
         .collect();
 
     for (key, value) in d_args {
-        config_builder = config_builder.set_override(key, value).map_err(|e| {
-            Error::from(format!("configuration error: {:#?}", e))
-        })?;
+        config_builder = config_builder.set_override(key, value).map_err(Error::Config)?;
     }
 
-    let config = Arc::new(config_builder.build().map_err(|e| {
-        Error::from(format!("configuration error: {:#?}", e))
-    })?);
-    config_context.register(BeanDef::builder()
-        .name("config")
-        .ty(ty)
-        .get(Arc::new(move |_| { Ok(config.clone()) }))
-        .build())?;
+    Ok(config_builder)
+}
 
-    Ok(config_context)
+fn add_properties(mut config_builder: ConfigBuilder<DefaultState>, properties: &[(String, String)]) -> Result<ConfigBuilder<DefaultState>, Error> {
+    for (key, value) in properties {
+        config_builder = config_builder.set_override(key, value.as_str()).map_err(Error::Config)?;
+    }
+    Ok(config_builder)
 }
 
 #[cfg(test)]
 mod tests {
+    use config::Map;
+
     use super::*;
     use crate::config::PropertyResolver;
 
@@ -110,6 +181,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn should_compute_typed_template_values() {
         std::env::set_var("APP_server.port", "8080");
         std::env::set_var("APP_enabled", "true");
@@ -123,4 +195,42 @@ mod tests {
         std::env::remove_var("APP_server_port");
         std::env::remove_var("APP_enabled");
     }
+
+    #[test]
+    fn should_derive_profile_config_files() {
+        let files = get_profile_config_files(&["app.yaml".to_string(), "conf/app.yml".to_string()], &["dev".to_string(), "local".to_string()]);
+        assert_eq!(files, vec!["app-dev.yaml", "conf/app-dev.yml", "app-local.yaml", "conf/app-local.yml"]);
+    }
+
+    #[test]
+    fn should_load_profile_config_files_on_top_of_base_files() {
+        let dir = std::env::temp_dir().join(format!("vine-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.yaml"), "vine.profiles.active: dev\ngreeting: base\nname: vine\n").unwrap();
+        std::fs::write(dir.join("app-dev.yaml"), "greeting: dev\n").unwrap();
+
+        let config_file = dir.join("app.yaml").to_string_lossy().to_string();
+        let context = get_config_context(vec![config_file]).unwrap();
+        let resolver = context.get_bean::<dyn PropertyResolver + Send + Sync>("config").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(get_active_profiles(resolver.as_ref()), vec!["dev"]);
+        assert_eq!(resolver.get_string("greeting"), Some("dev".to_string()));
+        assert_eq!(resolver.get_string("name"), Some("vine".to_string()));
+    }
+
+    #[test]
+    fn should_use_inline_properties_and_ignore_environment_variables() {
+        let properties = vec![("inline.key".to_string(), "inline_value".to_string())];
+        let environment = app_environment().source(Some(Map::from([("APP_env.key".to_string(), "from_env".to_string())])));
+
+        let context = build_config_context(&[], |config_builder| add_properties(add_overrides(config_builder, environment.clone())?, &properties)).unwrap();
+        let resolver = context.get_bean::<dyn PropertyResolver + Send + Sync>("config").unwrap();
+        assert_eq!(resolver.get_string("env.key"), Some("from_env".to_string()));
+
+        let context = get_inline_config_context(vec![], properties.clone()).unwrap();
+        let resolver = context.get_bean::<dyn PropertyResolver + Send + Sync>("config").unwrap();
+        assert_eq!(resolver.get_string("inline.key"), Some("inline_value".to_string()));
+        assert_eq!(resolver.get_string("env.key"), None);
+    }
 }
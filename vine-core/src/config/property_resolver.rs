@@ -12,7 +12,7 @@ pub trait PropertyResolver {
                 None => {
                     // ${property} - no default, must exist
                     self.get_string(&cap[1])
-                        .ok_or(Error::from(format!("Property '{}' not found and no default value provided", &cap[1])))?
+                        .ok_or_else(|| Error::PropertyMissing { key: cap[1].to_string() })?
                 },
                 Some((prop, default_template)) => {
                     // ${property:default} - use default if property doesn't exist
@@ -38,62 +38,80 @@ pub trait PropertyResolver {
     }
 
     fn compute_template_value_as_bool(&self, template: &str) -> Result<bool, Error> {
-        self.compute_template_value(template)?.parse::<bool>()
-            .map_err(|e| Error::from(format!("failed to parse bool: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<bool>()
+            .map_err(|e| Error::PropertyParse { value, ty: "bool", source: Box::new(e) })
     }
 
     fn compute_template_value_as_i8(&self, template: &str) -> Result<i8, Error> {
-        self.compute_template_value(template)?.parse::<i8>()
-            .map_err(|e| Error::from(format!("failed to parse i8: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<i8>()
+            .map_err(|e| Error::PropertyParse { value, ty: "i8", source: Box::new(e) })
     }
 
     fn compute_template_value_as_i16(&self, template: &str) -> Result<i16, Error> {
-        self.compute_template_value(template)?.parse::<i16>()
-            .map_err(|e| Error::from(format!("failed to parse i16: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<i16>()
+            .map_err(|e| Error::PropertyParse { value, ty: "i16", source: Box::new(e) })
     }
 
     fn compute_template_value_as_i32(&self, template: &str) -> Result<i32, Error> {
-        self.compute_template_value(template)?.parse::<i32>()
-            .map_err(|e| Error::from(format!("failed to parse i32: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<i32>()
+            .map_err(|e| Error::PropertyParse { value, ty: "i32", source: Box::new(e) })
     }
 
     fn compute_template_value_as_i64(&self, template: &str) -> Result<i64, Error> {
-        self.compute_template_value(template)?.parse::<i64>()
-            .map_err(|e| Error::from(format!("failed to parse i64: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<i64>()
+            .map_err(|e| Error::PropertyParse { value, ty: "i64", source: Box::new(e) })
     }
 
     fn compute_template_value_as_u8(&self, template: &str) -> Result<u8, Error> {
-        self.compute_template_value(template)?.parse::<u8>()
-            .map_err(|e| Error::from(format!("failed to parse u8: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<u8>()
+            .map_err(|e| Error::PropertyParse { value, ty: "u8", source: Box::new(e) })
     }
 
     fn compute_template_value_as_u16(&self, template: &str) -> Result<u16, Error> {
-        self.compute_template_value(template)?.parse::<u16>()
-            .map_err(|e| Error::from(format!("failed to parse u16: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<u16>()
+            .map_err(|e| Error::PropertyParse { value, ty: "u16", source: Box::new(e) })
     }
 
     fn compute_template_value_as_u32(&self, template: &str) -> Result<u32, Error> {
-        self.compute_template_value(template)?.parse::<u32>()
-            .map_err(|e| Error::from(format!("failed to parse u32: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<u32>()
+            .map_err(|e| Error::PropertyParse { value, ty: "u32", source: Box::new(e) })
     }
 
     fn compute_template_value_as_u64(&self, template: &str) -> Result<u64, Error> {
-        self.compute_template_value(template)?.parse::<u64>()
-            .map_err(|e| Error::from(format!("failed to parse u64: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<u64>()
+            .map_err(|e| Error::PropertyParse { value, ty: "u64", source: Box::new(e) })
     }
 
     fn compute_template_value_as_f32(&self, template: &str) -> Result<f32, Error> {
-        self.compute_template_value(template)?.parse::<f32>()
-            .map_err(|e| Error::from(format!("failed to parse f32: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<f32>()
+            .map_err(|e| Error::PropertyParse { value, ty: "f32", source: Box::new(e) })
     }
 
     fn compute_template_value_as_f64(&self, template: &str) -> Result<f64, Error> {
-        self.compute_template_value(template)?.parse::<f64>()
-            .map_err(|e| Error::from(format!("failed to parse f64: {}", e)))
+        let value = self.compute_template_value(template)?;
+        value.parse::<f64>()
+            .map_err(|e| Error::PropertyParse { value, ty: "f64", source: Box::new(e) })
     }
 
     fn get_string(&self, key: &str) -> Option<String>;
 
+    /// Returns a list given either as a sequence or as a comma separated string.
+    ///
+    /// The default implementation only supports comma separated strings.
+    fn get_list(&self, key: &str) -> Option<Vec<String>> {
+        self.get_string(key).map(|values| split_list(&values))
+    }
+
     fn get_bool(&self, key: &str) -> Option<bool>;
 
     fn get_i8(&self, key: &str) -> Option<i8>;
@@ -117,12 +135,26 @@ pub trait PropertyResolver {
     fn get_f64(&self, key: &str) -> Option<f64>;
 }
 
+fn split_list(values: &str) -> Vec<String> {
+    values.split(',')
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
 
 impl PropertyResolver for Config {
     fn get_string(&self, key: &str) -> Option<String> {
         self.get_string(key).ok()
     }
 
+    fn get_list(&self, key: &str) -> Option<Vec<String>> {
+        if let Ok(values) = self.get_array(key) {
+            return Some(values.into_iter().filter_map(|value| value.into_string().ok()).collect());
+        }
+
+        self.get_string(key).ok().map(|values| split_list(&values))
+    }
+
     fn get_bool(&self, key: &str) -> Option<bool> {
         self.get_bool(key).ok()
     }
@@ -202,6 +234,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn should_compute_template_value_as_bool() {
         let config = Config::builder()
             .set_default("bool_true", "true").unwrap()
@@ -316,6 +349,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn should_compute_template_value_as_f32() {
         let config = Config::builder()
             .set_default("num", "3.14").unwrap()
@@ -328,6 +362,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn should_compute_template_value_as_f64() {
         let config = Config::builder()
             .set_default("num", "3.141592653589793").unwrap()
@@ -458,6 +493,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn should_get_f32() {
         let config = Config::builder()
             .set_default("num", 3.14).unwrap()
@@ -470,6 +506,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn should_get_f64() {
         let config = Config::builder()
             .set_default("num", 3.141592653589793).unwrap()
@@ -480,7 +517,16 @@ mod tests {
         assert_eq!(PropertyResolver::get_f64(&config, "negative"), Some(-2.718281828459045));
         assert_eq!(PropertyResolver::get_f64(&config, "missing"), None);
     }
-}
-
 
+    #[test]
+    fn should_get_lists_from_sequences_and_comma_separated_strings() {
+        let config = Config::builder()
+            .set_default("sequence", vec!["a", "b"]).unwrap()
+            .set_default("string", "a, b,,c").unwrap()
+            .build().unwrap();
 
+        assert_eq!(PropertyResolver::get_list(&config, "sequence"), Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(PropertyResolver::get_list(&config, "string"), Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+        assert_eq!(PropertyResolver::get_list(&config, "missing"), None);
+    }
+}
@@ -1,18 +1,53 @@
 use linkme::distributed_slice;
-use log::debug;
+use log::{debug, trace};
 use crate::context::context::Context;
 use crate::core::Error;
 
+pub type SetupFn = fn(&Context) -> Result<(), Error>;
+
+/// Setup fns which are always run, e.g. the ones registering the trait aliases of
+/// `#[injectable]` or the listeners of `#[event_listener]`, which add no bean of their own.
 #[distributed_slice]
-pub static SETUP: [fn(&Context) -> Result<(), Error>] = [..];
+pub static SETUP: [SetupFn] = [..];
+
+/// An entry of [`NAMED_SETUP`], named after the bean it registers so that it can be filtered
+/// (see [`get_filtered_auto_register_context`]).
+pub struct Setup {
+    pub name: &'static str,
+    pub setup: SetupFn,
+}
+
+/// Setup fns registering the beans declared with `#[derive(Bean)]`, `#[bean]` and the other
+/// macros, named after their bean.
+#[distributed_slice]
+pub static NAMED_SETUP: [Setup] = [..];
 
 pub fn get_auto_register_context() -> Result<Context, Error> {
+    get_filtered_auto_register_context(|_| true)
+}
+
+/// Runs every [`SETUP`] fn and only the [`NAMED_SETUP`] entries whose name is accepted by `filter`.
+pub fn get_filtered_auto_register_context(filter: impl Fn(&str) -> bool) -> Result<Context, Error> {
     debug!("setup auto registered context");
 
     let context = Context::new("auto-registered");
+    setup_auto_registered(&context, filter)?;
+    Ok(context)
+}
+
+/// Runs every [`SETUP`] fn and the [`NAMED_SETUP`] entries whose name is accepted by `filter`
+/// against the given Context.
+pub fn setup_auto_registered(context: &Context, filter: impl Fn(&str) -> bool) -> Result<(), Error> {
     for setup_fn in SETUP {
-        (setup_fn)(&context)?;
+        (setup_fn)(context)?;
     }
 
-    Ok(context)
-}
\ No newline at end of file
+    for setup in NAMED_SETUP {
+        if !filter(setup.name) {
+            trace!("skipping setup {:?}", setup.name);
+            continue;
+        }
+        (setup.setup)(context)?;
+    }
+    Ok(())
+}
@@ -1,28 +1,53 @@
 use std::any::{type_name, TypeId};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::sync::Arc;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 
 use dashmap::DashMap;
 use log::{debug, trace, warn};
 
+use crate::config::{get_active_profiles, PropertyResolver};
+use crate::context::graph::DependencyGraph;
+use crate::context::override_policy::OverridePolicy;
 use crate::core::{DynBean, Error};
 use crate::core::bean_def::BeanDef;
+use crate::core::condition::Condition;
+use crate::core::dependency::DependencyKind;
+use crate::core::init_fn_def::{INIT_EXCLUDE_PROPERTY, InitFnDef, sort_init_fns};
+use crate::core::interceptor::MethodInterceptor;
+use crate::core::lifecycle;
+use crate::core::order::Ordered;
+use crate::core::post_processor::BeanPostProcessor;
+use crate::core::scope::{PROTOTYPE, Scope, SINGLETON};
 use crate::core::ty::Type;
 
-type InitContextFn = Arc<dyn Fn(&Context) -> Result<(), Error> + Send + Sync>;
+pub type InitContextFn = Arc<dyn Fn(&Context) -> Result<(), Error> + Send + Sync>;
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 #[derive(Clone)]
 pub struct Context {
     inner: Arc<InnerContext>,
+    // names of the beans currently being created through this Context handle, outermost first
+    creation_path: Arc<Vec<String>>,
+    // whether this handle creates the post-processors and their dependencies, which are not post-processed
+    creating_post_processors: bool,
 }
 
 struct InnerContext {
     name: String,
     beans: Arc<DashMap<String, DynBean>>,
+    // names of the cached singleton beans in creation order
+    created: Mutex<Vec<String>>,
     bean_defs: Arc<DashMap<String, Arc<BeanDef>>>,
     contexts: Arc<DashMap<String, Arc<Context>>>,
-    init_fns: DashMap<String, InitContextFn>
+    scopes: DashMap<String, Arc<dyn Scope + Send + Sync>>,
+    init_fns: DashMap<String, Arc<InitFnDef>>,
+    // the policy set explicitly, see Context::override_policy
+    override_policy: Mutex<Option<OverridePolicy>>,
+    // whether the conditions of the BeanDefs are matched by name, reset when BeanDefs change
+    matched_conditions: DashMap<String, bool>,
 }
 
 impl Context {
@@ -31,10 +56,16 @@ impl Context {
             inner: Arc::new(InnerContext {
                 name: name.to_string(),
                 beans: Default::default(),
+                created: Default::default(),
                 bean_defs: Default::default(),
                 contexts: Default::default(),
+                scopes: Default::default(),
                 init_fns: Default::default(),
-            })
+                override_policy: Default::default(),
+                matched_conditions: Default::default(),
+            }),
+            creation_path: Default::default(),
+            creating_post_processors: false,
         }
     }
 
@@ -42,24 +73,160 @@ impl Context {
         &self.inner.name
     }
 
-    pub fn add_context(&self, context: Context) {
-        // TODO: missed feature (context allow overrides) - if override use warn log. Also think about context property to allow overrides
+    /// Policy applied to the names already in use when registering beans, adding child contexts
+    /// and init fns, [`OverridePolicy::Replace`] by default. Until a policy is set, [`Context::register`]
+    /// still rejects a bean whose name is already registered.
+    pub fn override_policy(&self) -> OverridePolicy {
+        self.inner.override_policy.lock().unwrap().unwrap_or_default()
+    }
+
+    pub fn set_override_policy(&self, override_policy: OverridePolicy) {
+        *self.inner.override_policy.lock().unwrap() = Some(override_policy);
+    }
+
+    /// Adds a child context. Its beans and init fns whose names are already in use within this
+    /// Context are handled by the [`OverridePolicy`] of this Context, as the child context itself.
+    pub fn add_context(&self, context: Context) -> Result<(), Error> {
+        let override_policy = self.override_policy();
+        if self.inner.contexts.contains_key(context.name()) {
+            match override_policy {
+                OverridePolicy::Reject => {
+                    let error = Error::DuplicateContext { name: context.name().to_string(), context: self.to_string() };
+                    warn!("{}", &error);
+                    return Err(error);
+                },
+                OverridePolicy::KeepFirst => {
+                    debug!("ignoring {} already added to {}", &context, self);
+                    return Ok(());
+                },
+                OverridePolicy::Replace => {
+                    warn!("replacing {} within {}", &context, self);
+                    self.inner.contexts.remove(context.name());
+                },
+            }
+        }
+
+        let overridden_defs: Vec<_> = context.inner.collect_bean_defs().into_iter()
+            .filter(|def| self.inner.get_bean_def(def.name()).is_some())
+            .collect();
+        let existing_init_fns = self.inner.get_init_context_fns();
+        let overridden_init_fns: Vec<_> = context.inner.get_init_context_fns().into_keys()
+            .filter(|name| existing_init_fns.contains_key(name))
+            .collect();
+
+        match override_policy {
+            OverridePolicy::Reject => {
+                if let Some(bean_def) = overridden_defs.first() {
+                    let error = Error::DuplicateBean {
+                        name: bean_def.name().to_string(),
+                        ty: bean_def.ty().name().to_string(),
+                        context: self.to_string(),
+                    };
+                    warn!("{}", &error);
+                    return Err(error);
+                }
+                if let Some(name) = overridden_init_fns.first() {
+                    let error = Error::DuplicateInitFn { name: name.clone(), context: self.to_string() };
+                    warn!("{}", &error);
+                    return Err(error);
+                }
+            },
+            OverridePolicy::KeepFirst => {
+                for bean_def in &overridden_defs {
+                    debug!("ignoring {} of {} already registered within {}", bean_def, &context, self);
+                    context.inner.remove_bean_def(bean_def.name());
+                }
+                for name in &overridden_init_fns {
+                    debug!("ignoring init fn {:?} of {} already added to {}", name, &context, self);
+                    context.inner.remove_init_fn(name);
+                }
+            },
+            OverridePolicy::Replace => {
+                for bean_def in &overridden_defs {
+                    warn!("overriding {} within {} by {}", bean_def, self, &context);
+                    self.inner.remove_bean_def(bean_def.name());
+                }
+                for name in &overridden_init_fns {
+                    warn!("overriding init fn {:?} within {} by {}", name, self, &context);
+                    self.inner.remove_init_fn(name);
+                }
+            },
+        }
+
         self.inner.contexts.insert(context.name().to_string(), Arc::new(context));
+        self.inner.matched_conditions.clear();
+        Ok(())
+    }
+
+    /// Adds an init fn without ordering constraints, see [`Context::add_init_fn_def`].
+    pub fn add_init_fn(&self, name: &str, init_fn: InitContextFn) -> Result<(), Error>{
+        self.add_init_fn_def(InitFnDef::builder().name(name).init_fn(init_fn).build())
+    }
+
+    pub fn add_init_fn_def(&self, init_fn_def: InitFnDef) -> Result<(), Error> {
+        let name = init_fn_def.name();
+        if self.inner.get_init_context_fns().contains_key(name) {
+            match self.override_policy() {
+                OverridePolicy::Reject => {
+                    let error = Error::DuplicateInitFn { name: name.to_string(), context: self.to_string() };
+                    warn!("{}", &error);
+                    return Err(error);
+                },
+                OverridePolicy::KeepFirst => {
+                    debug!("ignoring init fn {:?} already added to {}", name, self);
+                    return Ok(());
+                },
+                OverridePolicy::Replace => {
+                    warn!("overriding init fn {:?} within {}", name, self);
+                    self.inner.remove_init_fn(name);
+                },
+            }
+        }
+
+        self.inner.init_fns.insert(name.to_string(), Arc::new(init_fn_def));
+        Ok(())
     }
 
-    pub fn add_init_fn(&self, name: &str, init_fn: Arc<dyn Fn(&Context) -> Result<(), Error> + Send + Sync>) -> Result<(), Error>{
-        // TODO: missed feature (context allow init overrides) - if override use warn log. Also think about context property to allow overrides
-        self.inner.init_fns.insert(name.to_string(), init_fn);
+    pub fn register_scope(&self, scope: Arc<dyn Scope + Send + Sync>) -> Result<(), Error> {
+        let scope_name = scope.name().to_string();
+        if scope_name == SINGLETON || scope_name == PROTOTYPE || self.inner.get_scope(&scope_name).is_some() {
+            let error = Error::DuplicateScope { name: scope_name, context: self.to_string() };
+            warn!("{}", &error);
+            return Err(error);
+        }
+
+        trace!("registering Scope(name={}) within {}", &scope_name, self);
+        self.inner.scopes.insert(scope_name, scope);
         Ok(())
     }
 
+    /// Runs the init fns of this Context and its child contexts in the order described by
+    /// [`InitFnDef`], except the ones listed in the `vine.init.exclude` property.
+    ///
+    /// The conditions of the registered BeanDefs are evaluated beforehand, see [`Condition`].
     pub fn init_contexts(&self) -> Result<(), Error> {
-        let init_fns = self.inner.get_init_context_fns();
+        self.evaluate_conditions();
+
+        let excluded = self.get_bean::<dyn PropertyResolver + Send + Sync>("config")
+            .ok()
+            .and_then(|config| config.get_list(INIT_EXCLUDE_PROPERTY))
+            .unwrap_or_default();
 
-        // TODO: missed feature (disable init function by name)
-        for (init_fn_name, init_fn) in init_fns.iter() {
-            trace!("execute init fn with name {:?}", init_fn_name);
-            init_fn(self)?;
+        let init_fns = self.inner.get_init_context_fns().into_values()
+            .filter(|init_fn| {
+                let excluded = excluded.iter().any(|name| name == init_fn.name());
+                if excluded {
+                    debug!("skipping excluded {} in {}", init_fn, self);
+                }
+                !excluded
+            })
+            .collect();
+
+        let init_fns = sort_init_fns(init_fns)
+            .map_err(|path| Error::CircularDependency { path, context: self.to_string() })?;
+        for init_fn in init_fns {
+            trace!("execute {} in {}", &init_fn, self);
+            init_fn.call(self)?;
         }
 
         Ok(())
@@ -67,63 +234,606 @@ impl Context {
 
     pub fn register(&self, bean_def: impl Into<BeanDef>) -> Result<(), Error> {
         let bean_def = bean_def.into();
-        if let Some(_) = self.inner.get_bean_def(bean_def.name()) {
-            warn!("failed to register duplicated BeanDef(name={}, type={}) in {}", bean_def.name(), bean_def.ty().name(), self);
-            return Err(Error::from(format!("failed to register duplicated BeanDef(name={}, type={}) in {}", bean_def.name(), bean_def.ty().name(), self)));
+        if let Some(existing) = self.inner.get_bean_def(bean_def.name()) {
+            let override_policy = self.inner.override_policy.lock().unwrap().unwrap_or(OverridePolicy::Reject);
+            match override_policy {
+                OverridePolicy::Reject => {
+                    let error = Error::DuplicateBean {
+                        name: bean_def.name().to_string(),
+                        ty: bean_def.ty().name().to_string(),
+                        context: self.to_string(),
+                    };
+                    warn!("{}", &error);
+                    return Err(error);
+                },
+                OverridePolicy::KeepFirst => {
+                    debug!("ignoring {}, {} is already registered within {}", &bean_def, &existing, self);
+                    return Ok(());
+                },
+                OverridePolicy::Replace => {
+                    warn!("overriding {} by {} within {}", &existing, &bean_def, self);
+                    self.inner.remove_bean_def(bean_def.name());
+                },
+            }
         };
 
         trace!("registering {} within {}", &bean_def, self);
         self.inner.bean_defs.insert(bean_def.name().to_string(), Arc::new(bean_def));
+        self.inner.matched_conditions.clear();
         Ok(())
     }
 
+    /// Checks whether a bean is registered with the given name, without creating it.
+    pub fn contains_bean_def(&self, name: &str) -> bool {
+        self.inner.get_bean_def(name).is_some()
+    }
+
     pub fn get_bean<T: ?Sized + 'static>(&self, name: &str) -> Result<Arc<T>, Error> {
+        let dyn_bean = self.get_dyn_bean(name, type_name::<T>())?;
+        Type::downcast::<T>(dyn_bean)
+    }
+
+    /// Async form of [`Context::get_bean`]: async factories are awaited instead of blocked on,
+    /// together with the async factories of their declared dependencies.
+    pub async fn get_bean_async<T: ?Sized + 'static>(&self, name: &str) -> Result<Arc<T>, Error> {
+        let dyn_bean = self.get_dyn_bean_async(name, type_name::<T>()).await?;
+        Type::downcast::<T>(dyn_bean)
+    }
+
+    pub fn get_primary_bean<T: ?Sized + 'static>(&self) -> Result<Arc<T>, Error> {
+        match self.get_primary_bean_def(&TypeId::of::<T>(), type_name::<T>())? {
+            Some(bean_def) => self.get_bean::<T>(bean_def.name()),
+            None => {
+                let error = Error::BeanNotFound { name: None, ty: type_name::<T>().to_string(), context: self.to_string() };
+                warn!("{}", &error);
+                Err(error)
+            },
+        }
+    }
+
+    /// Async form of [`Context::get_primary_bean`].
+    pub async fn get_primary_bean_async<T: ?Sized + 'static>(&self) -> Result<Arc<T>, Error> {
+        match self.get_primary_bean_def(&TypeId::of::<T>(), type_name::<T>())? {
+            Some(bean_def) => self.get_bean_async::<T>(bean_def.name()).await,
+            None => {
+                let error = Error::BeanNotFound { name: None, ty: type_name::<T>().to_string(), context: self.to_string() };
+                warn!("{}", &error);
+                Err(error)
+            },
+        }
+    }
+
+    /// Like [`Context::get_primary_bean`], but returns None when no BeanDef is assignable to `T`.
+    /// Ambiguous candidates and creation failures are still reported as errors.
+    pub fn get_optional_primary_bean<T: ?Sized + 'static>(&self) -> Result<Option<Arc<T>>, Error> {
+        match self.get_primary_bean_def(&TypeId::of::<T>(), type_name::<T>())? {
+            Some(bean_def) => self.get_bean::<T>(bean_def.name()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`Context::get_bean`], but returns None when no BeanDef with the name is registered
+    /// or its conditions are not matched. Creation failures are still reported as errors.
+    pub fn get_optional_bean<T: ?Sized + 'static>(&self, name: &str) -> Result<Option<Arc<T>>, Error> {
+        match self.inner.get_bean_def(name) {
+            Some(bean_def) if self.is_matched(&bean_def) => self.get_bean::<T>(name).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns every bean assignable to `T`, sorted by [`Ordered`] or the order of their BeanDef
+    /// and then by name.
+    pub fn get_beans<T: ?Sized + 'static>(&self) -> Result<Vec<Arc<T>>, Error> {
+        let beans = self.get_beans_with_names::<T>()?;
+        Ok(beans.into_iter().map(|(_, bean)| bean).collect())
+    }
+
+    /// Returns every bean assignable to `T` together with its name, in the order of [`Context::get_beans`].
+    pub fn get_beans_with_names<T: ?Sized + 'static>(&self) -> Result<Vec<(String, Arc<T>)>, Error> {
+        let type_id = TypeId::of::<T>();
+
+        let bean_defs = self.inner.get_bean_defs_by_type(self, &type_id);
+        self.get_ordered_beans(&bean_defs, type_name::<T>())?
+            .into_iter()
+            .map(|(name, dyn_bean)| Ok((name, Type::downcast::<T>(dyn_bean)?)))
+            .collect()
+    }
+
+    /// Builds the graph of the beans whose conditions are matched and their declared dependencies.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut bean_defs = self.inner.get_bean_defs_within_context(self);
+        bean_defs.sort_by(|a, b| a.name().cmp(b.name()));
+        DependencyGraph::build(&bean_defs)
+    }
+
+    /// Creates every non-lazy singleton bean of this Context and its child contexts, so that
+    /// misconfigured beans fail the startup instead of the first request using them.
+    ///
+    /// All failures are collected and reported at once. Async factories are awaited. When the
+    /// `vine.main.lazy-initialization` property is true, only the singletons which cannot be
+    /// created by the sync lookups are, i.e. the ones with an async factory or
+    /// [`AsyncInitializingBean`](lifecycle::AsyncInitializingBean) callback.
+    pub async fn instantiate_singletons(&self) -> Result<(), Error> {
+        let lazy_initialization = self.get_bean::<dyn PropertyResolver + Send + Sync>("config")
+            .ok()
+            .and_then(|config| config.get_bool("vine.main.lazy-initialization"))
+            .unwrap_or(false);
+        if lazy_initialization {
+            debug!("skipping eager instantiation of sync singletons in {}", self);
+        }
+
+        let mut bean_defs: Vec<_> = self.inner.get_bean_defs_within_context(self).into_iter()
+            .filter(|def| def.scope() == SINGLETON)
+            .filter(|def| match lazy_initialization {
+                true => def.is_async() || lifecycle::is_async_initialized(def.ty()),
+                false => !def.is_lazy(),
+            })
+            .collect();
+        bean_defs.sort_by(|a, b| a.name().cmp(b.name()));
+        debug!("instantiating {} singletons in {}", bean_defs.len(), self);
+
+        let mut errors: Vec<Error> = Vec::new();
+        for bean_def in bean_defs {
+            let Err(error) = self.get_dyn_bean_async(bean_def.name(), bean_def.ty().name()).await else { continue };
+
+            // a failing dependency is reported once, not again for every bean depending on it
+            if !errors.iter().any(|reported| same_failure(reported, &error)) {
+                errors.push(error);
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.pop().unwrap()),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    /// Destroys the cached singleton beans of this Context and its child contexts in reverse
+    /// creation order, calling their [`DisposableBean`](lifecycle::DisposableBean) callbacks.
+    pub async fn destroy(&self) -> Result<(), Error> {
+        let created = std::mem::take(&mut *self.inner.created.lock().unwrap());
+        debug!("destroying {} beans of {}", created.len(), self);
+
+        let mut errors = Vec::new();
+        for name in created.iter().rev() {
+            let Some((_, dyn_bean)) = self.inner.beans.remove(name) else { continue };
+            let Some(bean_def) = self.inner.get_bean_def(name) else { continue };
+
+            trace!("destroying Bean(name={}, type={}) in {}", name, bean_def.ty().name(), self);
+            if let Err(error) = lifecycle::destroy(bean_def.ty(), &dyn_bean).await {
+                warn!("failed to destroy Bean(name={}, type={}) in {}: {}", name, bean_def.ty().name(), self, &error);
+                errors.push(error);
+            }
+        }
+        self.inner.beans.clear();
+
+        let contexts: Vec<_> = self.inner.contexts.iter().map(|ctx| ctx.value().clone()).collect();
+        for ctx in contexts {
+            if let Err(error) = Box::pin(ctx.destroy()).await {
+                errors.push(error);
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.pop().unwrap()),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+}
+
+impl Context {
+    /// Handle to the same Context which is not bound to the bean currently being created, for
+    /// lookups deferred until after the creation.
+    pub(crate) fn detached(&self) -> Context {
+        Context { inner: self.inner.clone(), creation_path: Default::default(), creating_post_processors: false }
+    }
+
+    /// Returns the only candidate or the only primary one, None without candidates.
+    fn get_primary_bean_def(&self, type_id: &TypeId, ty: &str) -> Result<Option<Arc<BeanDef>>, Error> {
+        let mut candidates = self.inner.get_bean_defs_by_type(self, type_id);
+        if candidates.len() <= 1 {
+            return Ok(candidates.pop());
+        }
+
+        let mut primaries: Vec<_> = candidates.iter().filter(|def| def.is_primary()).collect();
+        if primaries.len() == 1 {
+            return Ok(primaries.pop().cloned());
+        }
+
+        let mut names: Vec<_> = candidates.iter().map(|def| def.name().to_string()).collect();
+        names.sort();
+
+        let error = Error::AmbiguousBean {
+            ty: ty.to_string(),
+            context: self.to_string(),
+            candidates: names,
+            primaries: primaries.len(),
+        };
+        warn!("{}", &error);
+        Err(error)
+    }
+
+    fn get_dyn_bean(&self, name: &str, ty: &str) -> Result<DynBean, Error> {
         if let Some(dyn_bean) = self.inner.get_bean(name) {
-            return Type::downcast::<T>(dyn_bean);
+            return Ok(dyn_bean);
+        }
+        // the bean may be a post-processor or one of their dependencies
+        if self.prepare_post_processors()? {
+            if let Some(dyn_bean) = self.inner.get_bean(name) {
+                return Ok(dyn_bean);
+            }
         }
 
+        let bean_def = self.get_available_bean_def(name, ty)?;
+        match bean_def.scope() {
+            SINGLETON => {},
+            PROTOTYPE => {
+                trace!("creating prototype Bean(name={}, type={}) in {}", name, bean_def.ty().name(), self);
+                return self.create_bean(&bean_def);
+            },
+            _ => return self.get_scoped_bean(&bean_def, ty),
+        }
+
+        let dyn_bean = self.create_bean(&bean_def)?;
+        self.cache_singleton(&bean_def, dyn_bean)
+    }
+
+    /// Async form of [`Context::get_dyn_bean`]. Custom scopes have a synchronous API, so scoped
+    /// beans are still created through the blocking path.
+    fn get_dyn_bean_async<'a>(&'a self, name: &'a str, ty: &'a str) -> BoxFuture<'a, Result<DynBean, Error>> {
+        Box::pin(async move {
+            if let Some(dyn_bean) = self.inner.get_bean(name) {
+                return Ok(dyn_bean);
+            }
+            if self.prepare_post_processors()? {
+                if let Some(dyn_bean) = self.inner.get_bean(name) {
+                    return Ok(dyn_bean);
+                }
+            }
+
+            let bean_def = self.get_available_bean_def(name, ty)?;
+            match bean_def.scope() {
+                SINGLETON => {},
+                PROTOTYPE => {
+                    trace!("creating prototype Bean(name={}, type={}) in {}", name, bean_def.ty().name(), self);
+                    return self.create_bean_async(&bean_def).await;
+                },
+                _ => return self.get_scoped_bean(&bean_def, ty),
+            }
+
+            let dyn_bean = self.create_bean_async(&bean_def).await?;
+            self.cache_singleton(&bean_def, dyn_bean)
+        })
+    }
+
+    /// Creates the beans sorted by [`Ordered`] or the order of their BeanDef and then by name.
+    fn get_ordered_beans(&self, bean_defs: &[Arc<BeanDef>], ty: &str) -> Result<Vec<(String, DynBean)>, Error> {
+        let mut beans = bean_defs.iter()
+            .map(|def| {
+                let dyn_bean = self.get_dyn_bean(def.name(), ty)?;
+                let order = match def.ty().assignable(&TypeId::of::<dyn Ordered + Send + Sync>()) {
+                    true => Type::downcast::<dyn Ordered + Send + Sync>(dyn_bean.clone())?.order(),
+                    false => def.order(),
+                };
+                Ok((order, def.name().to_string(), dyn_bean))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        beans.sort_by(|(order, name, _), (other_order, other_name, _)| order.cmp(other_order).then_with(|| name.cmp(other_name)));
+        Ok(beans.into_iter().map(|(_, name, dyn_bean)| (name, dyn_bean)).collect())
+    }
+
+    fn get_available_bean_def(&self, name: &str, ty: &str) -> Result<Arc<BeanDef>, Error> {
         let Some(bean_def) = self.inner.get_bean_def(name) else {
-            warn!("cannot resolve Bean(name={}, type={}) in {}", name, type_name::<T>(), self);
-            return Err(Error::from(format!("cannot resolve Bean(name={}, type={}) in {}", name, type_name::<T>(), self)));
+            let error = Error::BeanNotFound {
+                name: Some(name.to_string()),
+                ty: ty.to_string(),
+                context: self.to_string(),
+            };
+            warn!("{}", &error);
+            return Err(error);
+        };
+
+        if !self.is_matched(&bean_def) {
+            let error = Error::BeanUnavailable {
+                name: name.to_string(),
+                ty: ty.to_string(),
+                context: self.to_string(),
+                reason: format!("conditions of {} are not matched", &bean_def),
+            };
+            warn!("{}", &error);
+            return Err(error);
+        }
+
+        Ok(bean_def)
+    }
+
+    fn get_scoped_bean(&self, bean_def: &Arc<BeanDef>, ty: &str) -> Result<DynBean, Error> {
+        let name = bean_def.name();
+        let scope_name = bean_def.scope();
+        let Some(scope) = self.inner.get_scope(scope_name) else {
+            let error = Error::BeanUnavailable {
+                name: name.to_string(),
+                ty: ty.to_string(),
+                context: self.to_string(),
+                reason: format!("unknown scope {}", scope_name),
+            };
+            warn!("{}", &error);
+            return Err(error);
         };
 
-        let (name, dyn_bean) = bean_def.get(self)?;
-        if let Some(_) = self.inner.beans.insert(name.clone(), dyn_bean.clone()) {
+        trace!("getting Bean(name={}, type={}) from scope {} in {}", name, bean_def.ty().name(), scope_name, self);
+        scope.get(name, &|| self.create_bean(bean_def))
+    }
+
+    fn cache_singleton(&self, bean_def: &BeanDef, dyn_bean: DynBean) -> Result<DynBean, Error> {
+        let name = bean_def.name().to_string();
+        if self.inner.beans.insert(name.clone(), dyn_bean.clone()).is_some() {
             warn!("unexpected duplicated bean has been created Bean(name={}, type={}) in {}", &name, bean_def.ty().name(), self);
-            return Err(Error::from(format!("unexpected duplicated bean has been created Bean(name={}, type={}) in {}", name, bean_def.ty().name(), self)));
+            return Err(Error::DuplicateBean { name, ty: bean_def.ty().name().to_string(), context: self.to_string() });
         };
+        self.inner.created.lock().unwrap().push(name.clone());
 
         debug!("Bean(name={}, type={}) has been added to {}", &name, bean_def.ty().name(), self);
-        return Type::downcast::<T>(dyn_bean);
+        Ok(dyn_bean)
     }
 
-    pub fn get_primary_bean<T: ?Sized + 'static>(&self) -> Result<Arc<T>, Error> {
-        let type_id = TypeId::of::<T>();
-        let mut candidates = self.inner.get_bean_defs_by_type(self, &type_id);
-        match candidates.len() {
-            0 => {
-                Err(Error::from(""))
+    fn create_bean(&self, bean_def: &BeanDef) -> Result<DynBean, Error> {
+        self.check_sync_creation(bean_def)?;
+        let ctx = self.enter(bean_def)?;
+        bean_def.get(&ctx)
+            .and_then(|(_, dyn_bean)| {
+                lifecycle::initialize(bean_def.ty(), &dyn_bean)?;
+                ctx.post_process(bean_def, dyn_bean)
+            })
+            .map_err(|error| ctx.creation_error(bean_def, error))
+    }
+
+    /// Beans with an async factory or [`AsyncInitializingBean`](lifecycle::AsyncInitializingBean)
+    /// callback are only created by the async lookups and [`Context::instantiate_singletons`],
+    /// the sync ones fail instead of blocking a runtime.
+    fn check_sync_creation(&self, bean_def: &BeanDef) -> Result<(), Error> {
+        let reason = if bean_def.is_async() {
+            "it has an async factory, use get_bean_async"
+        } else if lifecycle::is_async_initialized(bean_def.ty()) {
+            "it is an AsyncInitializingBean, use get_bean_async"
+        } else {
+            return Ok(());
+        };
+
+        let error = Error::BeanUnavailable {
+            name: bean_def.name().to_string(),
+            ty: bean_def.ty().name().to_string(),
+            context: self.to_string(),
+            reason: reason.to_string(),
+        };
+        warn!("{}", &error);
+        Err(error)
+    }
+
+    /// Resolves the declared singleton dependencies asynchronously before awaiting the factory,
+    /// so the factory finds them cached instead of blocking on their creation.
+    async fn create_bean_async(&self, bean_def: &BeanDef) -> Result<DynBean, Error> {
+        let ctx = self.enter(bean_def)?;
+        let result = async {
+            ctx.resolve_dependencies_async(bean_def).await?;
+            let (_, dyn_bean) = bean_def.get_async(&ctx).await?;
+            lifecycle::initialize_async(bean_def.ty(), &dyn_bean).await?;
+            ctx.post_process(bean_def, dyn_bean)
+        }.await;
+
+        result.map_err(|error| ctx.creation_error(bean_def, error))
+    }
+
+    async fn resolve_dependencies_async(&self, bean_def: &BeanDef) -> Result<(), Error> {
+        for dependency in bean_def.dependencies().iter().filter(|dependency| !dependency.is_deferred()) {
+            let names = match dependency.kind() {
+                DependencyKind::Qualified(name) => vec![name.clone()],
+                DependencyKind::Primary => self.get_primary_bean_def(dependency.type_id(), dependency.type_name())?
+                    .map(|def| vec![def.name().to_string()])
+                    .unwrap_or_default(),
+                DependencyKind::All => self.inner.get_bean_defs_by_type(self, dependency.type_id()).iter()
+                    .map(|def| def.name().to_string())
+                    .collect(),
+            };
+
+            for name in names {
+                let is_singleton = self.inner.get_bean_def(&name).is_some_and(|def| def.scope() == SINGLETON);
+                if !is_singleton || (dependency.is_optional() && self.get_available_bean_def(&name, dependency.type_name()).is_err()) {
+                    continue;
+                }
+
+                trace!("resolving {} of {} asynchronously", dependency, bean_def);
+                self.get_dyn_bean_async(&name, dependency.type_name()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the [`BeanPostProcessor`]s and their dependencies in a phase of their own, before
+    /// the outermost bean being looked up, so that they are ready when it is post-processed. The
+    /// beans created in this phase are not post-processed. Returns whether the phase has run.
+    fn prepare_post_processors(&self) -> Result<bool, Error> {
+        if !self.creation_path.is_empty() || self.creating_post_processors {
+            return Ok(false);
+        }
+        self.get_post_processors()?;
+        Ok(true)
+    }
+
+    fn get_post_processors(&self) -> Result<Vec<(String, DynBean)>, Error> {
+        let type_id = TypeId::of::<dyn BeanPostProcessor + Send + Sync>();
+        let post_processor_defs = self.inner.get_bean_defs_by_type(self, &type_id);
+        if post_processor_defs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ctx = Context { creating_post_processors: true, ..self.clone() };
+        ctx.get_ordered_beans(&post_processor_defs, type_name::<dyn BeanPostProcessor + Send + Sync>())
+    }
+
+    /// Applies the [`BeanPostProcessor`]s to a created bean. Post-processors are not applied to
+    /// each other, nor to the beans created with them, see [`Context::prepare_post_processors`],
+    /// nor to the [`MethodInterceptor`]s run by the proxies they create.
+    fn post_process(&self, bean_def: &BeanDef, dyn_bean: DynBean) -> Result<DynBean, Error> {
+        let ty = bean_def.ty();
+        if self.creating_post_processors
+            || ty.assignable(&TypeId::of::<dyn BeanPostProcessor + Send + Sync>())
+            || ty.assignable(&TypeId::of::<dyn MethodInterceptor + Send + Sync>()) {
+            return Ok(dyn_bean);
+        }
+
+        let mut dyn_bean = dyn_bean;
+        for (name, post_processor) in self.get_post_processors()? {
+            trace!("post-processing {} with BeanPostProcessor(name={})", bean_def, &name);
+            dyn_bean = Type::downcast::<dyn BeanPostProcessor + Send + Sync>(post_processor)?
+                .post_process(bean_def.name(), bean_def.ty(), dyn_bean)?;
+        }
+        Ok(dyn_bean)
+    }
+
+    /// Returns a handle bound to the creation of the bean, failing if the bean is already being created.
+    fn enter(&self, bean_def: &BeanDef) -> Result<Context, Error> {
+        let mut creation_path = self.creation_path.as_ref().clone();
+        let circular = creation_path.iter().any(|name| name == bean_def.name());
+        creation_path.push(bean_def.name().to_string());
+
+        if circular {
+            let error = Error::CircularDependency { path: creation_path, context: self.to_string() };
+            warn!("{}", &error);
+            return Err(error);
+        }
+
+        Ok(Context {
+            inner: self.inner.clone(),
+            creation_path: Arc::new(creation_path),
+            creating_post_processors: self.creating_post_processors,
+        })
+    }
+
+    fn creation_error(&self, bean_def: &BeanDef, error: Error) -> Error {
+        match error {
+            // errors of nested beans already carry the deeper creation path
+            Error::BeanCreation { .. } | Error::CircularDependency { .. } => error,
+            error => {
+                let error = Error::BeanCreation {
+                    name: bean_def.name().to_string(),
+                    path: self.creation_path.as_ref().clone(),
+                    context: self.to_string(),
+                    source: Box::new(error),
+                };
+                warn!("{}", &error);
+                error
+            },
+        }
+    }
+
+    /// Evaluates the conditions of every BeanDef again, once all of them are registered.
+    fn evaluate_conditions(&self) {
+        self.inner.matched_conditions.clear();
+        for bean_def in self.inner.collect_bean_defs() {
+            self.is_matched(&bean_def);
+        }
+    }
+
+    /// Whether the conditions of the BeanDef are matched, evaluated on first use and cached until
+    /// BeanDefs are registered or removed.
+    fn is_matched(&self, bean_def: &BeanDef) -> bool {
+        if bean_def.conditions().is_empty() {
+            return true;
+        }
+        if let Some(matched) = self.inner.matched_conditions.get(bean_def.name()) {
+            return *matched;
+        }
+
+        let matched = self.conditions_match(bean_def, &mut Vec::new());
+        self.inner.matched_conditions.insert(bean_def.name().to_string(), matched);
+        matched
+    }
+
+    fn conditions_match(&self, bean_def: &BeanDef, visiting: &mut Vec<String>) -> bool {
+        if bean_def.conditions().is_empty() {
+            return true;
+        }
+
+        visiting.push(bean_def.name().to_string());
+        let matched = bean_def.conditions().iter().all(|condition| {
+            let matched = self.condition_matches(bean_def, condition, visiting);
+            trace!("{} of {} matched: {}", condition, bean_def, matched);
+            matched
+        });
+        visiting.pop();
+
+        matched
+    }
+
+    fn condition_matches(&self, bean_def: &BeanDef, condition: &Condition, visiting: &mut Vec<String>) -> bool {
+        match condition {
+            Condition::OnProperty { name, .. } => {
+                let value = self.get_config()
+                    .ok()
+                    .and_then(|config| config.get_string(name));
+                condition.matches_property(value.as_deref())
+            },
+            Condition::OnProfile { .. } => {
+                let active_profiles = self.get_config()
+                    .map(|config| get_active_profiles(config.as_ref()))
+                    .unwrap_or_default();
+                condition.matches_profiles(&active_profiles)
             },
-            1 => {
-                let bean_def = candidates.pop().unwrap();
-                self.get_bean::<T>(bean_def.name())
+            Condition::OnBean { type_id, .. } => {
+                self.get_condition_candidates(type_id, visiting).iter()
+                    .any(|def| self.conditions_match(def, visiting))
             },
-            _ => {
-                todo!("missed feature(primary beans) - add primary to BeanDef and use it to resolve primary bean")
+            Condition::OnMissingBean { type_id, .. } => {
+                // other fallbacks only replace this one when they precede it
+                !self.get_condition_candidates(type_id, visiting).iter()
+                    .filter(|def| !is_fallback(def) || (def.order(), def.name()) < (bean_def.order(), bean_def.name()))
+                    .any(|def| self.conditions_match(def, visiting))
             },
         }
     }
 
-    pub fn get_beans<T: ?Sized + 'static>(&self) -> Result<Vec<Arc<T>>, Error> {
-        let type_id = TypeId::of::<T>();
+    /// Config bean against which conditions are evaluated. It is needed to find the
+    /// post-processors, so it is created like them, without being post-processed.
+    fn get_config(&self) -> Result<Arc<dyn PropertyResolver + Send + Sync>, Error> {
+        Context { creating_post_processors: true, ..self.clone() }.get_bean::<dyn PropertyResolver + Send + Sync>("config")
+    }
 
-        self.inner.get_bean_defs_by_type(self, &type_id)
-            .iter()
-            .map(|def| self.get_bean::<T>(def.name()))
+    fn get_condition_candidates(&self, type_id: &TypeId, visiting: &[String]) -> Vec<Arc<BeanDef>> {
+        self.inner.collect_bean_defs().into_iter()
+            .filter(|def| !visiting.iter().any(|name| name == def.name()))
+            .filter(|def| def.ty().assignable(type_id))
             .collect()
     }
 }
 
+/// Whether the BeanDef is only registered when another bean is missing.
+fn is_fallback(bean_def: &BeanDef) -> bool {
+    bean_def.conditions().iter().any(|condition| matches!(condition, Condition::OnMissingBean { .. }))
+}
+
+/// Whether both errors are caused by the same failing bean or the same cycle.
+fn same_failure(error: &Error, other: &Error) -> bool {
+    let cycle = |path: &[String]| {
+        let last = path.last();
+        let start = path.iter().position(|name| Some(name) == last).unwrap_or(0);
+        let mut cycle = path[start..].to_vec();
+        cycle.sort();
+        cycle.dedup();
+        cycle
+    };
+
+    match (error, other) {
+        (Error::BeanCreation { name, .. }, Error::BeanCreation { name: other, .. }) => name == other,
+        (Error::CircularDependency { path, .. }, Error::CircularDependency { path: other, .. }) => cycle(path) == cycle(other),
+        _ => false,
+    }
+}
+
 impl Display for Context {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Context(name={})", &self.inner.name)
@@ -147,60 +857,91 @@ impl InnerContext {
         }
 
         trace!("cannot find BeanDef(name={}) in Context(name={})", name, &self.name);
-        return None;
+        None
     }
 
-    // TODO: missed feature (conditional beans) - use context to check conditional BeanDefs
-    fn get_bean_defs_within_context(&self, ctx: &Context) -> Vec<Arc<BeanDef>> {
-        let mut bean_defs = Vec::new();
-        let mut ctx_defs = self.bean_defs.iter()
-            // filter conditional beans here
+    /// Removes the BeanDef from this Context and its child contexts, with its cached bean.
+    fn remove_bean_def(&self, name: &str) {
+        self.bean_defs.remove(name);
+        self.beans.remove(name);
+        self.matched_conditions.clear();
+        for ctx in self.contexts.iter() {
+            ctx.inner.remove_bean_def(name);
+        }
+    }
+
+    fn remove_init_fn(&self, name: &str) {
+        self.init_fns.remove(name);
+        for ctx in self.contexts.iter() {
+            ctx.inner.remove_init_fn(name);
+        }
+    }
+
+    fn get_scope(&self, name: &str) -> Option<Arc<dyn Scope + Send + Sync>> {
+        if let Some(scope) = self.scopes.get(name) {
+            return Some(scope.value().clone());
+        }
+
+        self.contexts.iter().find_map(|ctx| ctx.inner.get_scope(name))
+    }
+
+    fn collect_bean_defs(&self) -> Vec<Arc<BeanDef>> {
+        let mut bean_defs: Vec<_> = self.bean_defs.iter()
             .map(|def| def.value().clone())
             .collect();
 
-        bean_defs.append(&mut ctx_defs);
         for child_ctx in self.contexts.iter() {
-            let mut ctx_defs = child_ctx.inner.get_bean_defs_within_context(ctx);
+            let mut ctx_defs = child_ctx.inner.collect_bean_defs();
             bean_defs.append(&mut ctx_defs);
         }
 
-        return bean_defs;
+        bean_defs
+    }
+
+    fn get_bean_defs_within_context(&self, ctx: &Context) -> Vec<Arc<BeanDef>> {
+        self.collect_bean_defs().into_iter()
+            .filter(|def| ctx.is_matched(def))
+            .collect()
     }
 
     fn get_bean_defs_by_type(&self, ctx: &Context, type_id: &TypeId) -> Vec<Arc<BeanDef>> {
-        self.get_bean_defs_within_context(ctx).into_iter()
+        self.collect_bean_defs().into_iter()
             .filter(|def| def.ty().assignable(type_id))
+            .filter(|def| ctx.is_matched(def))
             .collect()
     }
 
-    fn get_init_context_fns(&self) -> HashMap<String, InitContextFn> {
+    fn get_init_context_fns(&self) -> HashMap<String, Arc<InitFnDef>> {
         let mut fns = HashMap::new();
         for ctx in self.contexts.iter() {
-            let mut ctx_fns: Vec<_> = ctx.inner.get_init_context_fns().into_iter().collect();
-            while let Some((key, value)) = ctx_fns.pop() {
-                fns.insert(key, value);
-            }
-        }
-
-        let mut ctx_fns: Vec<_> = self.init_fns.iter()
-            .map(|item_ref| (item_ref.key().clone(), item_ref.value().clone()))
-            .collect();
-
-        while let Some((key, value)) = ctx_fns.pop() {
-            fns.insert(key, value);
+            fns.extend(ctx.inner.get_init_context_fns());
         }
 
-        return fns;
+        fns.extend(self.init_fns.iter().map(|item_ref| (item_ref.key().clone(), item_ref.value().clone())));
+        fns
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use crate::config::PropertyResolver;
     use crate::context::context::Context;
-    use crate::core::bean_def::BeanDef;
-    use crate::core::Error;
+    use crate::context::override_policy::OverridePolicy;
+    use crate::context::provider::{Lazy, Provider};
+    use crate::core::bean_def::{BeanDef, BeanDefBuilder};
+    use crate::core::condition::Condition;
+    use crate::core::dependency::{Dependency, DependencyKind};
+    use crate::core::{DynBean, Error};
+    use crate::core::init_fn_def::InitFnDef;
+    use crate::core::lifecycle::{AsyncDisposableBean, InitializingBean};
+    use crate::core::order::Ordered;
+    use crate::core::post_processor::BeanPostProcessor;
+    use crate::core::scope::{PROTOTYPE, Scope, SINGLETON};
     use crate::core::ty::Type;
 
     struct TestBean { name: &'static str, }
@@ -210,6 +951,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_question_mark)]
     fn register_types_bean_def_then_should_return_and_cast_to_struct_and_dyn_trait() -> Result<(), Error>{
         let ctx = Context::new("test-context");
 
@@ -240,6 +982,7 @@ mod tests {
 
 
     #[test]
+    #[allow(clippy::needless_question_mark)]
     fn should_create_and_get_bean_with_dyn_dep() -> Result<(), Error>{
         let ctx = Context::new("test-context");
 
@@ -276,4 +1019,786 @@ mod tests {
         assert_eq!(bean.dyn_dep.name(), "instance_of_testBean");
         Ok(())
     }
-}
\ No newline at end of file
+
+    struct OtherTestBean { name: &'static str, }
+    impl TestTrait for OtherTestBean {
+        fn name(&self) -> &'static str { self.name }
+    }
+
+    /// TestBean named `testBean` which returns `name`, completed by the tests, e.g. with a condition.
+    fn test_bean_def(name: &'static str) -> BeanDefBuilder {
+        let ty = Type::of::<TestBean>();
+        ty.add_downcast::<TestBean>(Arc::downcast::<TestBean>);
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<TestBean>(b)?));
+        BeanDef::builder()
+            .ty(ty)
+            .name("testBean")
+            .get(Arc::new(move |_ctx| Ok(Arc::new(TestBean { name }))))
+    }
+
+    fn register_test_trait_candidates(ctx: &Context, primary: Option<&str>) -> Result<(), Error> {
+        ctx.register(test_bean_def("instance_of_testBean").primary(primary == Some("testBean")).build())?;
+
+        let ty = Type::of::<OtherTestBean>();
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<OtherTestBean>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("otherTestBean")
+            .primary(primary == Some("otherTestBean"))
+            .get(Arc::new(|_ctx| Ok(Arc::new(OtherTestBean { name: "instance_of_otherTestBean" }))))
+            .build())
+    }
+
+    #[test]
+    fn should_resolve_primary_bean_among_multiple_candidates() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, Some("otherTestBean"))?;
+
+        let bean = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(bean.name(), "instance_of_otherTestBean");
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_to_resolve_primary_bean_without_primary_candidate() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, None)?;
+
+        let Err(error) = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>() else { panic!("expected error") };
+        let Error::AmbiguousBean { candidates, primaries, .. } = &error else { panic!("unexpected error: {}", error) };
+        assert_eq!(candidates, &vec!["otherTestBean".to_string(), "testBean".to_string()]);
+        assert_eq!(*primaries, 0);
+        assert!(error.to_string().contains("none of them is marked as primary"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_to_resolve_primary_bean_without_candidates() {
+        let ctx = Context::new("test-context");
+
+        let Err(error) = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>() else { panic!("expected error") };
+        let Error::BeanNotFound { name: None, ty, .. } = &error else { panic!("unexpected error: {}", error) };
+        assert!(ty.contains("TestTrait"), "{}", error);
+    }
+
+    fn register_config(ctx: &Context, properties: &[(&str, &str)]) -> Result<(), Error> {
+        let mut builder = config::Config::builder();
+        for (key, value) in properties {
+            builder = builder.set_override(*key, *value).unwrap();
+        }
+        let config = Arc::new(builder.build().unwrap());
+
+        let ty = Type::of::<config::Config>();
+        ty.add_downcast::<dyn PropertyResolver + Send + Sync>(|b| Ok(Arc::downcast::<config::Config>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("config")
+            .get(Arc::new(move |_ctx| Ok(config.clone())))
+            .build())
+    }
+
+    #[test]
+    fn should_register_bean_conditional_on_property() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_config(&ctx, &[("feature.x.enabled", "true")])?;
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_property("feature.x.enabled", Some("true"))).build())?;
+
+        let bean = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(bean.name(), "instance_of_testBean");
+        Ok(())
+    }
+
+    #[test]
+    fn should_skip_bean_conditional_on_property_with_different_value() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_config(&ctx, &[("feature.x.enabled", "false")])?;
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_property("feature.x.enabled", None)).build())?;
+
+        assert!(ctx.get_primary_bean::<dyn TestTrait + Sync + Send>().is_err());
+        assert!(ctx.get_bean::<dyn TestTrait + Sync + Send>("testBean").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_default_bean_conditional_on_missing_bean() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_missing_bean::<dyn TestTrait + Sync + Send>()).build())?;
+
+        let bean = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(bean.name(), "instance_of_testBean");
+
+        let app_ctx = Context::new("app-context");
+        let ty = Type::of::<OtherTestBean>();
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<OtherTestBean>(b)?));
+        app_ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("otherTestBean")
+            .get(Arc::new(|_ctx| Ok(Arc::new(OtherTestBean { name: "instance_of_otherTestBean" }))))
+            .build())?;
+
+        let root = Context::new("root");
+        root.add_context(ctx)?;
+        root.add_context(app_ctx)?;
+
+        let bean = root.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(bean.name(), "instance_of_otherTestBean");
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_first_of_competing_fallbacks() -> Result<(), Error> {
+        let fallback = |name: &'static str, order: i32| test_bean_def(name)
+            .name(name)
+            .order(order)
+            .condition(Condition::on_missing_bean::<dyn TestTrait + Sync + Send>())
+            .build();
+
+        let ctx = Context::new("test-context");
+        ctx.register(fallback("b", 0))?;
+        ctx.register(fallback("a", 0))?;
+        assert_eq!(ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?.name(), "a");
+        assert!(matches!(ctx.get_bean::<TestBean>("b"), Err(Error::BeanUnavailable { .. })));
+
+        let ctx = Context::new("test-context");
+        ctx.register(fallback("a", 0))?;
+        ctx.register(fallback("b", -1))?;
+        assert_eq!(ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?.name(), "b");
+        Ok(())
+    }
+
+    #[test]
+    fn should_register_bean_conditional_on_bean_only_when_bean_present() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_bean::<dyn PropertyResolver + Send + Sync>()).build())?;
+        assert!(ctx.get_primary_bean::<dyn TestTrait + Sync + Send>().is_err());
+
+        register_config(&ctx, &[])?;
+        let bean = ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(bean.name(), "instance_of_testBean");
+        Ok(())
+    }
+
+    #[test]
+    fn should_evaluate_conditions_once_until_bean_defs_change() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let config = Arc::new(config::Config::builder().set_override("feature.x.enabled", "true").unwrap().build().unwrap());
+        let resolved = Arc::new(AtomicUsize::new(0));
+        let counter = resolved.clone();
+        let ty = Type::of::<config::Config>();
+        ty.add_downcast::<dyn PropertyResolver + Send + Sync>(|b| Ok(Arc::downcast::<config::Config>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("config")
+            .scope(PROTOTYPE)
+            .get(Arc::new(move |_ctx| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(config.clone())
+            }))
+            .build())?;
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_property("feature.x.enabled", None)).build())?;
+
+        ctx.init_contexts()?;
+        let evaluated = resolved.load(Ordering::SeqCst);
+        ctx.get_bean::<TestBean>("testBean")?;
+        ctx.get_primary_bean::<dyn TestTrait + Sync + Send>()?;
+        assert_eq!(resolved.load(Ordering::SeqCst), evaluated);
+
+        ctx.register(BeanDef::builder()
+            .ty(Type::of::<OtherTestBean>())
+            .name("otherTestBean")
+            .get(Arc::new(|_ctx| Ok(Arc::new(OtherTestBean { name: "instance_of_otherTestBean" }))))
+            .build())?;
+        ctx.get_primary_bean::<TestBean>()?;
+        assert_eq!(resolved.load(Ordering::SeqCst), evaluated + 1);
+        Ok(())
+    }
+
+    struct CountingScope { created: AtomicUsize }
+    impl Scope for CountingScope {
+        fn name(&self) -> &str { "counting" }
+        fn get(&self, _bean_name: &str, create_fn: &dyn Fn() -> Result<DynBean, Error>) -> Result<DynBean, Error> {
+            self.created.fetch_add(1, Ordering::SeqCst);
+            create_fn()
+        }
+    }
+
+    #[test]
+    fn should_cache_singleton_beans() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").scope(SINGLETON).build())?;
+
+        let first = ctx.get_bean::<TestBean>("testBean")?;
+        let second = ctx.get_bean::<TestBean>("testBean")?;
+        assert!(Arc::ptr_eq(&first, &second));
+        Ok(())
+    }
+
+    #[test]
+    fn should_create_prototype_beans_on_every_resolution() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").scope(PROTOTYPE).build())?;
+
+        let first = ctx.get_bean::<TestBean>("testBean")?;
+        let second = ctx.get_bean::<TestBean>("testBean")?;
+        assert!(!Arc::ptr_eq(&first, &second));
+        Ok(())
+    }
+
+    #[test]
+    fn should_delegate_custom_scoped_beans_to_registered_scope() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let scope = Arc::new(CountingScope { created: AtomicUsize::new(0) });
+        ctx.register_scope(scope.clone())?;
+        ctx.register(test_bean_def("instance_of_testBean").scope("counting").build())?;
+
+        ctx.get_bean::<TestBean>("testBean")?;
+        ctx.get_bean::<TestBean>("testBean")?;
+        assert_eq!(scope.created.load(Ordering::SeqCst), 2);
+        assert!(ctx.register_scope(scope).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_to_resolve_bean_with_unknown_scope() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").scope("unknown").build())?;
+
+        let Err(error) = ctx.get_bean::<TestBean>("testBean") else { panic!("expected error") };
+        let Error::BeanUnavailable { reason, .. } = &error else { panic!("unexpected error: {}", error) };
+        assert_eq!(reason, "unknown scope unknown");
+        Ok(())
+    }
+
+    struct ChainedTestBean {
+        dyn_dep: Arc<dyn TestTrait + Sync + Send>
+    }
+    impl TestTrait for ChainedTestBean {
+        fn name(&self) -> &'static str { self.dyn_dep.name() }
+    }
+
+    fn register_dependent_test_bean(ctx: &Context, name: &'static str, dependency: &'static str) -> Result<(), Error> {
+        let ty = Type::of::<ChainedTestBean>();
+        ty.add_downcast::<ChainedTestBean>(Arc::downcast::<ChainedTestBean>);
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<ChainedTestBean>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name(name)
+            .get(Arc::new(move |ctx| Ok(Arc::new(ChainedTestBean { dyn_dep: ctx.get_bean(dependency)? }))))
+            .build())
+    }
+
+    #[test]
+    fn should_detect_circular_dependency_with_full_path() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_dependent_test_bean(&ctx, "a", "b")?;
+        register_dependent_test_bean(&ctx, "b", "c")?;
+        register_dependent_test_bean(&ctx, "c", "a")?;
+
+        let Err(error) = ctx.get_bean::<ChainedTestBean>("a") else { panic!("expected error") };
+        let Error::CircularDependency { path, .. } = &error else { panic!("unexpected error: {}", error) };
+        assert_eq!(path.join(" -> "), "a -> b -> c -> a");
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_creation_path_of_failed_dependency() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_dependent_test_bean(&ctx, "a", "b")?;
+        register_dependent_test_bean(&ctx, "b", "missing")?;
+
+        let Err(error) = ctx.get_bean::<ChainedTestBean>("a") else { panic!("expected error") };
+        let Error::BeanCreation { name, path, source, .. } = &error else { panic!("unexpected error: {}", error) };
+        assert_eq!(name, "b");
+        assert_eq!(path.join(" -> "), "a -> b");
+        assert!(matches!(source.as_ref(), Error::BeanNotFound { name: Some(name), .. } if name == "missing"));
+        Ok(())
+    }
+
+    struct LifecycleTestBean { name: &'static str, events: Arc<Mutex<Vec<String>>> }
+    impl InitializingBean for LifecycleTestBean {
+        fn after_properties_set(&self) -> Result<(), Error> {
+            self.events.lock().unwrap().push(format!("init {}", self.name));
+            Ok(())
+        }
+    }
+    #[async_trait]
+    impl AsyncDisposableBean for LifecycleTestBean {
+        async fn destroy(&self) -> Result<(), Error> {
+            self.events.lock().unwrap().push(format!("destroy {}", self.name));
+            Ok(())
+        }
+    }
+
+    fn register_lifecycle_test_bean(ctx: &Context, name: &'static str, dependency: Option<&'static str>, events: &Arc<Mutex<Vec<String>>>) -> Result<(), Error> {
+        let ty = Type::of::<LifecycleTestBean>();
+        ty.add_downcast::<LifecycleTestBean>(Arc::downcast::<LifecycleTestBean>);
+        ty.add_downcast::<dyn InitializingBean + Send + Sync>(|b| Ok(Arc::downcast::<LifecycleTestBean>(b)?));
+        ty.add_downcast::<dyn AsyncDisposableBean + Send + Sync>(|b| Ok(Arc::downcast::<LifecycleTestBean>(b)?));
+
+        let events = events.clone();
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name(name)
+            .get(Arc::new(move |ctx| {
+                if let Some(dependency) = dependency {
+                    ctx.get_bean::<LifecycleTestBean>(dependency)?;
+                }
+                Ok(Arc::new(LifecycleTestBean { name, events: events.clone() }))
+            }))
+            .build())
+    }
+
+    #[tokio::test]
+    async fn should_call_lifecycle_callbacks_and_destroy_in_reverse_creation_order() -> Result<(), Error> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let ctx = Context::new("test-context");
+        register_lifecycle_test_bean(&ctx, "pool", None, &events)?;
+        register_lifecycle_test_bean(&ctx, "repository", Some("pool"), &events)?;
+
+        ctx.get_bean::<LifecycleTestBean>("repository")?;
+        ctx.destroy().await?;
+
+        assert_eq!(*events.lock().unwrap(), vec!["init pool", "init repository", "destroy repository", "destroy pool"]);
+        assert!(ctx.inner.get_bean("pool").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_instantiate_non_lazy_singletons() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, None)?;
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        ctx.register(BeanDef::builder()
+            .ty(Type::of::<OtherTestBean>())
+            .name("lazyBean")
+            .lazy(true)
+            .get(Arc::new(move |_ctx| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(Arc::new(OtherTestBean { name: "instance_of_lazyBean" }))
+            }))
+            .build())?;
+
+        ctx.instantiate_singletons().await?;
+        assert!(ctx.inner.get_bean("testBean").is_some());
+        assert!(ctx.inner.get_bean("otherTestBean").is_some());
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_report_every_unresolvable_singleton() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_dependent_test_bean(&ctx, "a", "missing")?;
+        register_dependent_test_bean(&ctx, "b", "a")?;
+        register_dependent_test_bean(&ctx, "c", "d")?;
+        register_dependent_test_bean(&ctx, "d", "c")?;
+
+        let Err(Error::Multiple(errors)) = ctx.instantiate_singletons().await else { panic!("expected multiple errors") };
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], Error::BeanCreation { name, .. } if name == "a"));
+        assert!(matches!(&errors[1], Error::CircularDependency { .. }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_skip_instantiation_with_lazy_initialization_property() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_config(&ctx, &[("vine.main.lazy-initialization", "true")])?;
+        register_dependent_test_bean(&ctx, "a", "missing")?;
+
+        ctx.instantiate_singletons().await?;
+        assert!(ctx.inner.get_bean("config").is_some());
+        assert!(ctx.inner.get_bean("a").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn should_register_bean_only_for_active_profile() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_config(&ctx, &[("vine.profiles.active", "dev, local")])?;
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_profile(&["dev"])).build())?;
+        assert!(ctx.get_bean::<TestBean>("testBean").is_ok());
+
+        let ctx = Context::new("test-context");
+        register_config(&ctx, &[("vine.profiles.active", "dev")])?;
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_profile(&["prod", "!dev"])).build())?;
+        assert!(matches!(ctx.get_bean::<TestBean>("testBean"), Err(Error::BeanUnavailable { .. })));
+
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_profile(&["!prod"])).build())?;
+        assert!(ctx.get_bean::<TestBean>("testBean").is_ok());
+        Ok(())
+    }
+
+    struct LazyTestBean { dep: Lazy<ChainedTestBean> }
+    impl TestTrait for LazyTestBean {
+        fn name(&self) -> &'static str { "lazy" }
+    }
+
+    #[test]
+    fn should_break_construction_cycle_with_lazy_dependency() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let ty = Type::of::<LazyTestBean>();
+        ty.add_downcast::<LazyTestBean>(Arc::downcast::<LazyTestBean>);
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<LazyTestBean>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("a")
+            .get(Arc::new(|ctx| Ok(Arc::new(LazyTestBean { dep: Lazy::named(ctx, "b") }))))
+            .build())?;
+        register_dependent_test_bean(&ctx, "b", "a")?;
+
+        let a = ctx.get_bean::<LazyTestBean>("a")?;
+        assert!(ctx.inner.get_bean("b").is_none());
+
+        let b = a.dep.get()?;
+        assert_eq!(b.dyn_dep.name(), "lazy");
+        assert!(Arc::ptr_eq(&b, &a.dep.get()?));
+        Ok(())
+    }
+
+    #[test]
+    fn should_resolve_provider_on_every_call() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("instance_of_testBean").scope(PROTOTYPE).build())?;
+
+        let provider = Provider::<TestBean>::primary(&ctx);
+        let first = provider.get()?;
+        let second = provider.get()?;
+        assert!(!Arc::ptr_eq(&first, &second));
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_optional_beans() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        assert!(ctx.get_optional_primary_bean::<dyn TestTrait + Send + Sync>()?.is_none());
+        assert!(ctx.get_optional_bean::<TestBean>("testBean")?.is_none());
+
+        ctx.register(test_bean_def("instance_of_testBean").condition(Condition::on_property("feature.x.enabled", None)).build())?;
+        assert!(ctx.get_optional_bean::<TestBean>("testBean")?.is_none());
+
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, Some("testBean"))?;
+        assert_eq!(ctx.get_optional_primary_bean::<dyn TestTrait + Send + Sync>()?.unwrap().name(), "instance_of_testBean");
+        assert!(ctx.get_optional_bean::<TestBean>("testBean")?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_failures_of_optional_beans() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, None)?;
+        assert!(matches!(ctx.get_optional_primary_bean::<dyn TestTrait + Send + Sync>(), Err(Error::AmbiguousBean { .. })));
+
+        register_dependent_test_bean(&ctx, "a", "missing")?;
+        assert!(matches!(ctx.get_optional_bean::<ChainedTestBean>("a"), Err(Error::BeanCreation { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_beans_with_names() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, None)?;
+
+        let beans = ctx.get_beans_with_names::<dyn TestTrait + Send + Sync>()?;
+        let beans: Vec<_> = beans.iter().map(|(name, bean)| (name.as_str(), bean.name())).collect();
+        assert_eq!(beans, vec![("otherTestBean", "instance_of_otherTestBean"), ("testBean", "instance_of_testBean")]);
+        Ok(())
+    }
+
+    fn register_async_test_beans(ctx: &Context) -> Result<(), Error> {
+        let ty = Type::of::<TestBean>();
+        ty.add_downcast::<TestBean>(Arc::downcast::<TestBean>);
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<TestBean>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("asyncBean")
+            .get_async(|_ctx| async {
+                tokio::task::yield_now().await;
+                let bean: DynBean = Arc::new(TestBean { name: "instance_of_asyncBean" });
+                Ok(bean)
+            })
+            .build())?;
+
+        let ty = Type::of::<TestBeanWithDep>();
+        ty.add_downcast::<TestBeanWithDep>(Arc::downcast::<TestBeanWithDep>);
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("dependent")
+            .dependency(Dependency::qualified::<dyn TestTrait + Send + Sync>("dyn_dep", "asyncBean"))
+            .get(Arc::new(|ctx| Ok(Arc::new(TestBeanWithDep { dyn_dep: ctx.get_bean("asyncBean")? }))))
+            .build())
+    }
+
+    #[tokio::test]
+    async fn should_await_async_factories_of_declared_dependencies() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_async_test_beans(&ctx)?;
+
+        let bean = ctx.get_bean_async::<TestBeanWithDep>("dependent").await?;
+        assert_eq!(bean.dyn_dep.name(), "instance_of_asyncBean");
+        assert!(Arc::ptr_eq(&ctx.get_bean::<TestBean>("asyncBean")?, &ctx.get_bean_async::<TestBean>("asyncBean").await?));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reject_sync_lookups_of_async_factories() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_async_test_beans(&ctx)?;
+
+        let Err(error) = ctx.get_bean::<TestBeanWithDep>("dependent") else { panic!("expected error") };
+        let Error::BeanCreation { source, .. } = &error else { panic!("unexpected error: {}", error) };
+        let Error::BeanUnavailable { name, reason, .. } = source.as_ref() else { panic!("unexpected error: {}", error) };
+        assert_eq!(name, "asyncBean");
+        assert_eq!(reason, "it has an async factory, use get_bean_async");
+
+        ctx.instantiate_singletons().await?;
+        assert_eq!(ctx.get_bean::<TestBeanWithDep>("dependent")?.dyn_dep.name(), "instance_of_asyncBean");
+        Ok(())
+    }
+
+    struct DecoratedTestBean { inner: Arc<dyn TestTrait + Send + Sync> }
+    impl TestTrait for DecoratedTestBean {
+        fn name(&self) -> &'static str { if self.inner.name() == "instance_of_testBean" { "decorated" } else { "unexpected" } }
+    }
+
+    struct DecoratingPostProcessor { processed: Mutex<Vec<String>> }
+    impl BeanPostProcessor for DecoratingPostProcessor {
+        fn post_process(&self, name: &str, ty: &Type, bean: DynBean) -> Result<DynBean, Error> {
+            self.processed.lock().unwrap().push(name.to_string());
+            if !ty.assignable(&std::any::TypeId::of::<dyn TestTrait + Send + Sync>()) {
+                return Ok(bean);
+            }
+            let inner = Type::downcast::<dyn TestTrait + Send + Sync>(bean)?;
+            Ok(Arc::new(DecoratedTestBean { inner }))
+        }
+    }
+
+    #[test]
+    fn should_replace_beans_by_post_processors() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, Some("testBean"))?;
+
+        let ty = Type::of::<DecoratedTestBean>();
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<DecoratedTestBean>(b)?));
+
+        let post_processor = Arc::new(DecoratingPostProcessor { processed: Default::default() });
+        let ty = Type::of::<DecoratingPostProcessor>();
+        ty.add_downcast::<dyn BeanPostProcessor + Sync + Send>(|b| Ok(Arc::downcast::<DecoratingPostProcessor>(b)?));
+        let bean = post_processor.clone();
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("postProcessor")
+            .get(Arc::new(move |_ctx| Ok(bean.clone())))
+            .build())?;
+
+        let bean = ctx.get_bean::<dyn TestTrait + Send + Sync>("testBean")?;
+        assert_eq!(bean.name(), "decorated");
+        assert_eq!(*post_processor.processed.lock().unwrap(), vec!["testBean"]);
+        Ok(())
+    }
+
+    struct DependentPostProcessor { dep: Arc<TestBean>, processed: Mutex<Vec<String>> }
+    impl BeanPostProcessor for DependentPostProcessor {
+        fn post_process(&self, name: &str, _ty: &Type, bean: DynBean) -> Result<DynBean, Error> {
+            self.processed.lock().unwrap().push(format!("{} after {}", name, self.dep.name));
+            Ok(bean)
+        }
+    }
+
+    #[test]
+    fn should_create_post_processors_before_the_beans_they_depend_on_are_post_processed() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, None)?;
+
+        let ty = Type::of::<DependentPostProcessor>();
+        ty.add_downcast::<DependentPostProcessor>(Arc::downcast::<DependentPostProcessor>);
+        ty.add_downcast::<dyn BeanPostProcessor + Sync + Send>(|b| Ok(Arc::downcast::<DependentPostProcessor>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("postProcessor")
+            .get(Arc::new(|ctx| Ok(Arc::new(DependentPostProcessor { dep: ctx.get_bean("testBean")?, processed: Default::default() }))))
+            .build())?;
+
+        assert_eq!(ctx.get_bean::<TestBean>("testBean")?.name, "instance_of_testBean");
+        ctx.get_bean::<dyn TestTrait + Sync + Send>("otherTestBean")?;
+
+        let post_processor = ctx.get_bean::<DependentPostProcessor>("postProcessor")?;
+        assert_eq!(*post_processor.processed.lock().unwrap(), vec!["otherTestBean after instance_of_testBean"]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_build_dependency_graph_from_declared_dependencies() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        register_test_trait_candidates(&ctx, Some("testBean"))?;
+        ctx.register(BeanDef::builder()
+            .ty(Type::of::<TestBeanWithDep>())
+            .name("testBeanWithDep")
+            .dependency(Dependency::primary::<dyn TestTrait + Send + Sync>("dyn_dep"))
+            .dependency(Dependency::all::<dyn TestTrait + Send + Sync>("all_deps"))
+            .dependency(Dependency::qualified::<TestBean>("missing_dep", "missing"))
+            .get(Arc::new(|ctx| Ok(Arc::new(TestBeanWithDep { dyn_dep: ctx.get_primary_bean()? }))))
+            .build())?;
+
+        let graph = ctx.dependency_graph();
+        let names: Vec<_> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, vec!["otherTestBean", "testBean", "testBeanWithDep"]);
+
+        let edges: Vec<_> = graph.edges.iter().map(|edge| (edge.field.as_str(), edge.to.as_deref())).collect();
+        assert_eq!(edges, vec![
+            ("dyn_dep", Some("testBean")),
+            ("all_deps", Some("otherTestBean")),
+            ("all_deps", Some("testBean")),
+            ("missing_dep", None),
+        ]);
+        assert_eq!(graph.edges[3].kind, DependencyKind::Qualified("missing".to_string()));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"testBeanWithDep\" -> \"testBean\" [label=\"dyn_dep\"];"));
+        assert!(dot.contains("style=dashed, color=red"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["edges"][0]["kind"], "primary");
+        assert_eq!(json["edges"][3]["qualifier"], "missing");
+        assert!(json["edges"][3]["to"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn should_get_beans_sorted_by_order_and_name() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        for (name, order) in [("d", None), ("c", None), ("b", Some(10)), ("a", Some(20))] {
+            let mut builder = test_bean_def(name).name(name);
+            if let Some(order) = order {
+                builder = builder.order(order);
+            }
+            ctx.register(builder.build())?;
+        }
+
+        struct OrderedTestBean;
+        impl TestTrait for OrderedTestBean {
+            fn name(&self) -> &'static str { "ordered" }
+        }
+        impl Ordered for OrderedTestBean {
+            fn order(&self) -> i32 { 15 }
+        }
+        let ty = Type::of::<OrderedTestBean>();
+        ty.add_downcast::<dyn TestTrait + Sync + Send>(|b| Ok(Arc::downcast::<OrderedTestBean>(b)?));
+        ty.add_downcast::<dyn Ordered + Sync + Send>(|b| Ok(Arc::downcast::<OrderedTestBean>(b)?));
+        ctx.register(BeanDef::builder()
+            .ty(ty)
+            .name("ordered")
+            .order(100)
+            .get(Arc::new(|_ctx| Ok(Arc::new(OrderedTestBean))))
+            .build())?;
+
+        let names: Vec<_> = ctx.get_beans::<dyn TestTrait + Sync + Send>()?.iter().map(|bean| bean.name()).collect();
+        assert_eq!(names, vec!["b", "ordered", "a", "c", "d"]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_apply_override_policy_on_register() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        ctx.register(test_bean_def("first").build())?;
+        assert!(matches!(ctx.register(test_bean_def("second").build()), Err(Error::DuplicateBean { .. })));
+
+        ctx.set_override_policy(OverridePolicy::KeepFirst);
+        ctx.register(test_bean_def("second").build())?;
+        assert_eq!(ctx.get_bean::<dyn TestTrait + Sync + Send>("testBean")?.name(), "first");
+
+        let ctx = Context::new("test-context");
+        ctx.set_override_policy(OverridePolicy::Replace);
+        ctx.register(test_bean_def("first").build())?;
+        ctx.register(test_bean_def("second").build())?;
+        assert_eq!(ctx.get_bean::<dyn TestTrait + Sync + Send>("testBean")?.name(), "second");
+        Ok(())
+    }
+
+    #[test]
+    fn should_apply_override_policy_on_added_contexts() -> Result<(), Error> {
+        let child = |name: &str, bean: &'static str| -> Result<Context, Error> {
+            let ctx = Context::new(name);
+            ctx.register(test_bean_def(bean).build())?;
+            ctx.add_init_fn("init", Arc::new(|_| Ok(())))?;
+            Ok(ctx)
+        };
+
+        let root = Context::new("root");
+        root.set_override_policy(OverridePolicy::Reject);
+        root.add_context(child("library", "library")?)?;
+        assert!(matches!(root.add_context(child("library", "other")?), Err(Error::DuplicateContext { .. })));
+        assert!(matches!(root.add_context(child("app", "app")?), Err(Error::DuplicateBean { .. })));
+        assert!(matches!(root.add_init_fn("init", Arc::new(|_| Ok(()))), Err(Error::DuplicateInitFn { .. })));
+
+        root.set_override_policy(OverridePolicy::KeepFirst);
+        root.add_context(child("app", "app")?)?;
+        assert_eq!(root.get_bean::<dyn TestTrait + Sync + Send>("testBean")?.name(), "library");
+
+        let root = Context::new("root");
+        root.set_override_policy(OverridePolicy::Replace);
+        root.add_context(child("library", "library")?)?;
+        root.add_context(child("app", "app")?)?;
+        assert_eq!(root.get_bean::<dyn TestTrait + Sync + Send>("testBean")?.name(), "app");
+        assert_eq!(root.get_beans::<dyn TestTrait + Sync + Send>()?.len(), 1);
+        root.init_contexts()?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_added_contexts_and_init_fns_by_default() -> Result<(), Error> {
+        let root = Context::new("root");
+        assert_eq!(root.override_policy(), OverridePolicy::Replace);
+
+        let library = Context::new("library");
+        library.register(test_bean_def("library").build())?;
+        root.add_context(library)?;
+        let app = Context::new("library");
+        app.register(test_bean_def("app").build())?;
+        root.add_context(app)?;
+        assert_eq!(root.get_bean::<dyn TestTrait + Sync + Send>("testBean")?.name(), "app");
+
+        let called = Arc::new(AtomicBool::new(false));
+        let init_called = called.clone();
+        root.add_init_fn("init", Arc::new(|_| Err(Error::from("replaced init fn"))))?;
+        root.add_init_fn("init", Arc::new(move |_| {
+            init_called.store(true, Ordering::SeqCst);
+            Ok(())
+        }))?;
+        root.init_contexts()?;
+        assert!(called.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_init_fns_in_order_except_excluded_ones() -> Result<(), Error> {
+        let root = Context::new("root");
+        register_config(&root, &[("vine.init.exclude", "disabled")])?;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let init_fn = |name: &'static str| {
+            let calls = calls.clone();
+            InitFnDef::builder().name(name).init_fn(Arc::new(move |_| {
+                calls.lock().unwrap().push(name);
+                Ok(())
+            }))
+        };
+
+        let child = Context::new("child");
+        child.add_init_fn_def(init_fn("routes").after("web").build())?;
+        child.add_init_fn_def(init_fn("disabled").order(0).build())?;
+        root.add_context(child)?;
+        root.add_init_fn_def(init_fn("web").build())?;
+        root.add_init_fn_def(init_fn("metrics").before("web").build())?;
+
+        root.init_contexts()?;
+        assert_eq!(*calls.lock().unwrap(), vec!["metrics", "web", "routes"]);
+
+        root.add_init_fn_def(init_fn("cycle").before("metrics").after("routes").build())?;
+        assert!(matches!(root.init_contexts(), Err(Error::CircularDependency { .. })));
+        Ok(())
+    }
+}
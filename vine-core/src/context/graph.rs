@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::core::bean_def::BeanDef;
+use crate::core::dependency::{Dependency, DependencyKind};
+
+/// Wiring of the beans of a Context, derived from the declared [`Dependency`]s without creating any bean.
+pub struct DependencyGraph {
+    pub nodes: Vec<BeanNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+pub struct BeanNode {
+    pub name: String,
+    pub ty: String,
+    pub scope: String,
+    pub primary: bool,
+}
+
+pub struct DependencyEdge {
+    pub from: String,
+    /// The resolved bean, None when the dependency cannot be resolved.
+    pub to: Option<String>,
+    pub field: String,
+    pub ty: String,
+    pub kind: DependencyKind,
+    pub optional: bool,
+}
+
+impl DependencyGraph {
+    pub(crate) fn build(bean_defs: &[Arc<BeanDef>]) -> DependencyGraph {
+        let nodes = bean_defs.iter()
+            .map(|def| BeanNode {
+                name: def.name().to_string(),
+                ty: def.ty().name().to_string(),
+                scope: def.scope().to_string(),
+                primary: def.is_primary(),
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for bean_def in bean_defs {
+            for dependency in bean_def.dependencies() {
+                let edge = |to: Option<&str>| DependencyEdge {
+                    from: bean_def.name().to_string(),
+                    to: to.map(str::to_string),
+                    field: dependency.field().to_string(),
+                    ty: dependency.type_name().to_string(),
+                    kind: dependency.kind().clone(),
+                    optional: dependency.is_optional(),
+                };
+
+                match dependency.kind() {
+                    DependencyKind::Qualified(name) => {
+                        let to = bean_defs.iter().find(|def| def.name() == name);
+                        edges.push(edge(to.map(|def| def.name())));
+                    },
+                    DependencyKind::Primary => edges.push(edge(resolve_primary(bean_defs, dependency))),
+                    DependencyKind::All => {
+                        edges.extend(bean_defs.iter()
+                            .filter(|def| def.ty().assignable(dependency.type_id()))
+                            .map(|def| edge(Some(def.name()))));
+                    },
+                }
+            }
+        }
+
+        DependencyGraph { nodes, edges }
+    }
+
+    /// Renders the graph in Graphviz DOT format, unresolved dependencies are drawn dashed red
+    /// and unresolved optional ones are left out.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph beans {\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let style = if node.primary { ", style=bold" } else { "" };
+            dot.push_str(&format!("    {} [label={}{}];\n", quoted(&node.name), quoted(&format!("{}\n{}", node.name, node.ty)), style));
+        }
+
+        for edge in &self.edges {
+            match &edge.to {
+                Some(to) => {
+                    dot.push_str(&format!("    {} -> {} [label={}];\n", quoted(&edge.from), quoted(to), quoted(&edge.field)));
+                },
+                None if edge.optional => {},
+                None => {
+                    let missing = format!("{} ({})", edge.ty, edge.kind);
+                    dot.push_str(&format!("    {} [style=dashed, color=red];\n", quoted(&missing)));
+                    dot.push_str(&format!("    {} -> {} [label={}, style=dashed, color=red];\n", quoted(&edge.from), quoted(&missing), quoted(&edge.field)));
+                },
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+
+    pub fn to_json_value(&self) -> Value {
+        let nodes: Vec<_> = self.nodes.iter()
+            .map(|node| json!({
+                "name": node.name,
+                "type": node.ty,
+                "scope": node.scope,
+                "primary": node.primary,
+            }))
+            .collect();
+
+        let edges: Vec<_> = self.edges.iter()
+            .map(|edge| {
+                let (kind, qualifier) = match &edge.kind {
+                    DependencyKind::Qualified(name) => ("qualified", Some(name)),
+                    DependencyKind::Primary => ("primary", None),
+                    DependencyKind::All => ("all", None),
+                };
+                json!({
+                    "from": edge.from,
+                    "to": edge.to,
+                    "field": edge.field,
+                    "type": edge.ty,
+                    "kind": kind,
+                    "qualifier": qualifier,
+                    "optional": edge.optional,
+                })
+            })
+            .collect();
+
+        json!({ "nodes": nodes, "edges": edges })
+    }
+}
+
+/// Same rules as `Context::get_primary_bean`: the only candidate, otherwise the only primary one.
+fn resolve_primary<'a>(bean_defs: &'a [Arc<BeanDef>], dependency: &Dependency) -> Option<&'a str> {
+    let candidates: Vec<_> = bean_defs.iter()
+        .filter(|def| def.ty().assignable(dependency.type_id()))
+        .collect();
+
+    if candidates.len() == 1 {
+        return Some(candidates[0].name());
+    }
+
+    let primaries: Vec<_> = candidates.iter().filter(|def| def.is_primary()).collect();
+    match primaries.as_slice() {
+        [primary] => Some(primary.name()),
+        _ => None,
+    }
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
@@ -1,2 +1,8 @@
+#[allow(clippy::module_inception)]
 pub mod context;
-pub mod auto_register_context;
\ No newline at end of file
+pub mod auto_register_context;
+pub mod graph;
+pub mod provider;
+pub mod override_policy;
+pub mod module;
+pub mod registration;
\ No newline at end of file
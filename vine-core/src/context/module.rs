@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+
+use log::trace;
+
+use crate::context::auto_register_context::{setup_auto_registered, SetupFn};
+use crate::context::context::Context;
+use crate::context::registration::{Register, RegisterAs};
+use crate::core::Error;
+
+pub const AUTO_REGISTERED_MODULE: &str = "auto-registered";
+
+pub type ModuleFn = Box<dyn Fn(&Context) -> Result<(), Error> + Send + Sync>;
+
+/// A named group of beans and init fns, added to an App as a child Context of the same name.
+///
+/// Libraries expose their beans as a Module, e.g. `pub fn module() -> Module`, so that
+/// applications compose them explicitly with `App::builder().module(my_lib::module())`:
+/// ```ignore
+/// pub fn module() -> Module {
+///     Module::named("my-lib")
+///         .register::<ConnectionPool>()
+///         .register_as::<ConnectionPool, dyn HealthCheck + Send + Sync>()
+/// }
+/// ```
+pub struct Module {
+    name: String,
+    setups: Vec<ModuleFn>,
+}
+
+impl Module {
+    pub fn new(name: &str, setup: impl Fn(&Context) -> Result<(), Error> + Send + Sync + 'static) -> Module {
+        Module::named(name).setup(setup)
+    }
+
+    /// Creates an empty module, see [`Module::register`] and [`Module::setup`].
+    pub fn named(name: &str) -> Module {
+        Module { name: name.to_string(), setups: Vec::new() }
+    }
+
+    /// Adds a setup fn, run after the previous ones against the Context of the module.
+    pub fn setup(mut self, setup: impl Fn(&Context) -> Result<(), Error> + Send + Sync + 'static) -> Module {
+        self.setups.push(Box::new(setup));
+        self
+    }
+
+    /// Registers the beans of `T`, e.g. declared with `#[derive(Bean)]`.
+    pub fn register<T: Register>(self) -> Module {
+        let register: SetupFn = T::register;
+        self.setup(register)
+    }
+
+    /// Registers `T` as an implementation of `A`, e.g. declared with `#[injectable]`.
+    pub fn register_as<T: RegisterAs<A>, A: ?Sized>(self) -> Module {
+        let register_as: SetupFn = T::register_as;
+        self.setup(register_as)
+    }
+
+    /// Registers every entry of the `SETUP` and `NAMED_SETUP` distributed slices, i.e. the beans
+    /// declared with `#[derive(Bean)]`, `#[bean]` and the other macros of linked crates.
+    pub fn auto_registered() -> Module {
+        Module::auto_registered_filtered(|_| true)
+    }
+
+    /// Registers only the `NAMED_SETUP` entries whose bean name is accepted by `filter`, the
+    /// unnamed `SETUP` entries, e.g. trait aliases, are always registered.
+    pub fn auto_registered_filtered(filter: impl Fn(&str) -> bool + Send + Sync + 'static) -> Module {
+        Module::new(AUTO_REGISTERED_MODULE, move |context| setup_auto_registered(context, &filter))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Creates the Context of the module.
+    pub fn into_context(self) -> Result<Context, Error> {
+        trace!("setup module {}", &self.name);
+        let context = Context::new(&self.name);
+        for setup in &self.setups {
+            setup(&context)?;
+        }
+        Ok(context)
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Module(name={})", &self.name)
+    }
+}
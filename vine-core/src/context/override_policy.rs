@@ -0,0 +1,27 @@
+use crate::config::PropertyResolver;
+
+/// Property allowing the contexts added to the App to override beans, e.g. `web` or `config`.
+pub const ALLOW_BEAN_DEFINITION_OVERRIDING_PROPERTY: &str = "vine.main.allow-bean-definition-overriding";
+
+/// How a Context handles a bean, child context or init fn registered with a name already in use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverridePolicy {
+    /// The new entry replaces the existing one, with a warn log.
+    #[default]
+    Replace,
+    /// The registration fails.
+    Reject,
+    /// The existing entry is kept and the new one is ignored.
+    KeepFirst,
+}
+
+impl OverridePolicy {
+    /// [`OverridePolicy::Replace`] when [`ALLOW_BEAN_DEFINITION_OVERRIDING_PROPERTY`] is true,
+    /// [`OverridePolicy::Reject`] when it is false, and `None` when it is not set.
+    pub fn from_config(config: &dyn PropertyResolver) -> Option<OverridePolicy> {
+        config.get_bool(ALLOW_BEAN_DEFINITION_OVERRIDING_PROPERTY).map(|allowed| match allowed {
+            true => OverridePolicy::Replace,
+            false => OverridePolicy::Reject,
+        })
+    }
+}
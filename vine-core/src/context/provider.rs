@@ -0,0 +1,80 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::context::context::Context;
+use crate::core::Error;
+
+/// Injects a bean which is resolved on first use instead of when its owner is created.
+///
+/// Useful for heavy collaborators which are not always needed, and to break construction
+/// order cycles: `A { b: Lazy<B> }` can be created before `B { a: Arc<A> }`.
+pub struct Lazy<T: ?Sized + 'static> {
+    provider: Provider<T>,
+    bean: OnceLock<Arc<T>>,
+}
+
+/// Injects a handle which resolves the bean on every call, e.g. to get a new instance of a
+/// prototype scoped bean each time.
+pub struct Provider<T: ?Sized + 'static> {
+    context: Context,
+    name: Option<String>,
+    _marker: std::marker::PhantomData<fn() -> Arc<T>>,
+}
+
+impl<T: ?Sized + 'static> Lazy<T> {
+    /// Resolves the primary bean of type `T` on first use.
+    pub fn primary(context: &Context) -> Lazy<T> {
+        Lazy { provider: Provider::primary(context), bean: OnceLock::new() }
+    }
+
+    /// Resolves the bean with the given name on first use.
+    pub fn named(context: &Context, name: &str) -> Lazy<T> {
+        Lazy { provider: Provider::named(context, name), bean: OnceLock::new() }
+    }
+
+    /// Returns the bean, resolving it on the first call. Failures are not cached.
+    pub fn get(&self) -> Result<Arc<T>, Error> {
+        if let Some(bean) = self.bean.get() {
+            return Ok(bean.clone());
+        }
+
+        let bean = self.provider.get()?;
+        Ok(self.bean.get_or_init(|| bean).clone())
+    }
+
+    /// Async form of [`Lazy::get`], required for beans with an async factory.
+    pub async fn get_async(&self) -> Result<Arc<T>, Error> {
+        if let Some(bean) = self.bean.get() {
+            return Ok(bean.clone());
+        }
+
+        let bean = self.provider.get_async().await?;
+        Ok(self.bean.get_or_init(|| bean).clone())
+    }
+}
+
+impl<T: ?Sized + 'static> Provider<T> {
+    /// Resolves the primary bean of type `T` on every call.
+    pub fn primary(context: &Context) -> Provider<T> {
+        Provider { context: context.detached(), name: None, _marker: Default::default() }
+    }
+
+    /// Resolves the bean with the given name on every call.
+    pub fn named(context: &Context, name: &str) -> Provider<T> {
+        Provider { context: context.detached(), name: Some(name.to_string()), _marker: Default::default() }
+    }
+
+    pub fn get(&self) -> Result<Arc<T>, Error> {
+        match &self.name {
+            None => self.context.get_primary_bean::<T>(),
+            Some(name) => self.context.get_bean::<T>(name),
+        }
+    }
+
+    /// Async form of [`Provider::get`], required for beans with an async factory.
+    pub async fn get_async(&self) -> Result<Arc<T>, Error> {
+        match &self.name {
+            None => self.context.get_primary_bean_async::<T>().await,
+            Some(name) => self.context.get_bean_async::<T>(name).await,
+        }
+    }
+}
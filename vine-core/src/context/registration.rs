@@ -0,0 +1,19 @@
+use crate::context::context::Context;
+use crate::core::Error;
+
+/// Registers the beans of a type into a Context, without going through the `SETUP` distributed
+/// slices. Implemented by the code generated for `#[derive(Bean)]`, and for `#[bean]` by a type
+/// named after the fn.
+///
+/// Applications and libraries register them explicitly with `App::builder().register::<T>()`
+/// or `Module::named("my-lib").register::<T>()`.
+pub trait Register {
+    fn register(context: &Context) -> Result<(), Error>;
+}
+
+/// Registers the type as an implementation of `A`, e.g. `dyn Greeter + Send + Sync`, so that its
+/// beans can be looked up as `A`. Implemented by the code generated for `#[injectable]` and
+/// `#[event_listener]`, and for the routes of a `#[controller]` with `A = vine_axum::Routes`.
+pub trait RegisterAs<A: ?Sized> {
+    fn register_as(context: &Context) -> Result<(), Error>;
+}
@@ -1,22 +1,48 @@
 use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use log::trace;
 
 use crate::context::context::Context;
 use crate::core::{DynBean, Error};
+use crate::core::condition::Condition;
+use crate::core::dependency::Dependency;
+use crate::core::order::LOWEST_PRECEDENCE;
+use crate::core::scope::SINGLETON;
 use crate::core::ty::Type;
 
+pub type GetBeanFn = Arc<dyn Fn(&Context) -> Result<DynBean, Error> + Send + Sync>;
+pub type AsyncGetBeanFn = Arc<dyn Fn(Context) -> Pin<Box<dyn Future<Output = Result<DynBean, Error>> + Send>> + Send + Sync>;
+
+enum BeanFactory {
+    Sync(GetBeanFn),
+    Async(AsyncGetBeanFn),
+}
+
 pub struct BeanDef {
     name: String,
     ty: Arc<Type>,
-    get_fn: Arc<dyn Fn(&Context) -> Result<DynBean, Error> + Send + Sync>,
+    primary: bool,
+    lazy: bool,
+    order: i32,
+    scope: String,
+    conditions: Vec<Condition>,
+    dependencies: Vec<Dependency>,
+    factory: BeanFactory,
 }
 
 pub struct BeanDefBuilder {
     name: Option<String>,
     ty: Option<Arc<Type>>,
-    get_fn: Option<Arc<dyn Fn(&Context) -> Result<DynBean, Error> + Send + Sync>>,
+    primary: bool,
+    lazy: bool,
+    order: i32,
+    scope: Option<String>,
+    conditions: Vec<Condition>,
+    dependencies: Vec<Dependency>,
+    factory: Option<BeanFactory>,
 }
 
 impl BeanDef {
@@ -24,7 +50,13 @@ impl BeanDef {
         BeanDefBuilder {
             name: None,
             ty: None,
-            get_fn: None,
+            primary: false,
+            lazy: false,
+            order: LOWEST_PRECEDENCE,
+            scope: None,
+            conditions: Vec::new(),
+            dependencies: Vec::new(),
+            factory: None,
         }
     }
 
@@ -36,10 +68,63 @@ impl BeanDef {
         &self.ty
     }
 
+    pub fn is_primary(&self) -> bool {
+        self.primary
+    }
+
+    /// Lazy singletons are not instantiated on startup, but on first use.
+    pub fn is_lazy(&self) -> bool {
+        self.lazy
+    }
+
+    /// Position among the candidates returned by `Context::get_beans`, lower values first.
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    /// Injection points declared by the bean, used to inspect the wiring without creating it.
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    /// Whether the bean is created by an async factory, see [`BeanDefBuilder::get_async`].
+    pub fn is_async(&self) -> bool {
+        matches!(self.factory, BeanFactory::Async(_))
+    }
+
+    /// Creates the bean, failing for async factories which must be awaited with
+    /// [`BeanDef::get_async`].
     pub fn get(&self, context: &Context) -> Result<(String, DynBean), Error> {
         let name = self.name().to_string();
         trace!("getting Bean(name={}, type={}) with {}", &name, self.ty.name(), context);
-        let dyn_bean = self.get_fn.as_ref()(context)?;
+        let dyn_bean = match &self.factory {
+            BeanFactory::Sync(get_fn) => get_fn.as_ref()(context)?,
+            BeanFactory::Async(_) => return Err(Error::BeanUnavailable {
+                name,
+                ty: self.ty.name().to_string(),
+                context: context.to_string(),
+                reason: "it has an async factory, use get_bean_async".to_string(),
+            }),
+        };
+        Ok((name, dyn_bean))
+    }
+
+    /// Creates the bean, awaiting async factories.
+    pub async fn get_async(&self, context: &Context) -> Result<(String, DynBean), Error> {
+        let name = self.name().to_string();
+        trace!("getting Bean(name={}, type={}) asynchronously with {}", &name, self.ty.name(), context);
+        let dyn_bean = match &self.factory {
+            BeanFactory::Sync(get_fn) => get_fn.as_ref()(context)?,
+            BeanFactory::Async(get_fn) => get_fn.as_ref()(context.clone()).await?,
+        };
         Ok((name, dyn_bean))
     }
 }
@@ -62,19 +147,62 @@ impl BeanDefBuilder {
         self
     }
 
-    pub fn get(mut self, get_fn: Arc<dyn Fn(&Context) -> Result<DynBean, Error> + Send + Sync>) -> Self {
-        self.get_fn = Some(get_fn);
+    pub fn primary(mut self, primary: bool) -> Self {
+        self.primary = primary;
+        self
+    }
+
+    pub fn lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn dependency(mut self, dependency: Dependency) -> Self {
+        self.dependencies.push(dependency);
+        self
+    }
+
+    pub fn get(mut self, get_fn: GetBeanFn) -> Self {
+        self.factory = Some(BeanFactory::Sync(get_fn));
+        self
+    }
+
+    /// Sets an async factory. `Context::get_bean_async` awaits it after resolving the declared
+    /// dependencies, `Context::get_bean` rejects the bean unless it was already created.
+    pub fn get_async<F, Fut>(mut self, get_fn: F) -> Self
+    where
+        F: Fn(Context) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<DynBean, Error>> + Send + 'static,
+    {
+        self.factory = Some(BeanFactory::Async(Arc::new(move |ctx| Box::pin(get_fn(ctx)))));
         self
     }
 
     pub fn build(self) -> BeanDef {
         let ty = self.ty.unwrap();
-        let get_fn = self.get_fn.unwrap();
+        let factory = self.factory.unwrap();
         let name = match self.name {
             None => ty.name().to_string(),
             Some(name) => name,
         };
 
-        BeanDef { name, ty, get_fn, }
+        let scope = self.scope.unwrap_or_else(|| SINGLETON.to_string());
+
+        BeanDef { name, ty, primary: self.primary, lazy: self.lazy, order: self.order, scope, conditions: self.conditions, dependencies: self.dependencies, factory, }
     }
 }
\ No newline at end of file
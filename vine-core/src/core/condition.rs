@@ -0,0 +1,75 @@
+use std::any::{type_name, TypeId};
+use std::fmt::{Display, Formatter};
+
+/// A registration condition attached to a [`BeanDef`](crate::core::bean_def::BeanDef).
+///
+/// Conditions are evaluated by the [`Context`](crate::context::context::Context) on the first lookup of
+/// the BeanDef, and again for every BeanDef by [`Context::init_contexts`](crate::context::context::Context::init_contexts).
+/// Results are cached until bean definitions are registered or removed. A BeanDef whose
+/// conditions don't match behaves as if it was never registered.
+pub enum Condition {
+    /// Matches when the property exists and, if `having` is set, equals it (ignoring case).
+    /// Without `having` any value except `false` matches.
+    OnProperty { name: String, having: Option<String> },
+    /// Matches when at least one other BeanDef is assignable to the given type.
+    OnBean { type_id: TypeId, type_name: &'static str },
+    /// Matches when no other BeanDef is assignable to the given type.
+    ///
+    /// Among several BeanDefs which are themselves conditional on a missing bean, i.e. fallbacks,
+    /// only the first one by order, then by name, matches.
+    OnMissingBean { type_id: TypeId, type_name: &'static str },
+    /// Matches when any of the profiles is active. A profile prefixed with `!` matches when it is not active.
+    OnProfile { profiles: Vec<String> },
+}
+
+impl Condition {
+    pub fn on_property(name: &str, having: Option<&str>) -> Condition {
+        Condition::OnProperty {
+            name: name.to_string(),
+            having: having.map(|having| having.to_string()),
+        }
+    }
+
+    pub fn on_bean<T: ?Sized + 'static>() -> Condition {
+        Condition::OnBean { type_id: TypeId::of::<T>(), type_name: type_name::<T>() }
+    }
+
+    pub fn on_missing_bean<T: ?Sized + 'static>() -> Condition {
+        Condition::OnMissingBean { type_id: TypeId::of::<T>(), type_name: type_name::<T>() }
+    }
+
+    pub fn on_profile(profiles: &[&str]) -> Condition {
+        Condition::OnProfile { profiles: profiles.iter().map(|profile| profile.to_string()).collect() }
+    }
+
+    /// Checks the active profiles against this condition. Returns false for non-profile conditions.
+    pub fn matches_profiles(&self, active_profiles: &[String]) -> bool {
+        let Condition::OnProfile { profiles } = self else { return false };
+        profiles.iter().any(|profile| match profile.strip_prefix('!') {
+            None => active_profiles.contains(profile),
+            Some(profile) => !active_profiles.iter().any(|active| active == profile),
+        })
+    }
+
+    /// Checks a property value against this condition. Returns false for non-property conditions.
+    pub fn matches_property(&self, value: Option<&str>) -> bool {
+        let Condition::OnProperty { having, .. } = self else { return false };
+        match (value, having) {
+            (None, _) => false,
+            (Some(value), None) => !value.eq_ignore_ascii_case("false"),
+            (Some(value), Some(having)) => value.eq_ignore_ascii_case(having),
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::OnProperty { name, having: None } => write!(f, "OnProperty(name={})", name),
+            Condition::OnProperty { name, having: Some(having) } => write!(f, "OnProperty(name={}, having={})", name, having),
+            Condition::OnBean { type_name, .. } => write!(f, "OnBean(type={})", type_name),
+            Condition::OnMissingBean { type_name, .. } => write!(f, "OnMissingBean(type={})", type_name),
+            Condition::OnProfile { profiles } => write!(f, "OnProfile(profiles={})", profiles.join(",")),
+        }
+    }
+}
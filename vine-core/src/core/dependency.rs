@@ -0,0 +1,104 @@
+use std::any::{type_name, TypeId};
+use std::fmt::{Display, Formatter};
+
+/// How an injection point resolves its beans.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DependencyKind {
+    /// A single bean selected by `#[qualifier("name")]`.
+    Qualified(String),
+    /// The primary bean of the requested type.
+    Primary,
+    /// Every bean of the requested type (`Vec<Arc<T>>`).
+    All,
+}
+
+/// An injection point of a bean, recorded on its [`BeanDef`](crate::core::bean_def::BeanDef).
+#[derive(Clone, Debug)]
+pub struct Dependency {
+    field: String,
+    type_id: TypeId,
+    type_name: &'static str,
+    kind: DependencyKind,
+    optional: bool,
+    deferred: bool,
+}
+
+impl Dependency {
+    pub fn qualified<T: ?Sized + 'static>(field: &str, name: &str) -> Dependency {
+        Dependency::new::<T>(field, DependencyKind::Qualified(name.to_string()))
+    }
+
+    pub fn primary<T: ?Sized + 'static>(field: &str) -> Dependency {
+        Dependency::new::<T>(field, DependencyKind::Primary)
+    }
+
+    pub fn all<T: ?Sized + 'static>(field: &str) -> Dependency {
+        Dependency::new::<T>(field, DependencyKind::All)
+    }
+
+    fn new<T: ?Sized + 'static>(field: &str, kind: DependencyKind) -> Dependency {
+        Dependency {
+            field: field.to_string(),
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+            kind,
+            optional: false,
+            deferred: false,
+        }
+    }
+
+    /// Marks the dependency as optional (`Option<Arc<T>>`), it may remain unresolved.
+    pub fn optional(mut self) -> Dependency {
+        self.optional = true;
+        self
+    }
+
+    /// Marks the dependency as resolved after the bean has been created (`Lazy<T>`, `Provider<T>`).
+    pub fn deferred(mut self) -> Dependency {
+        self.deferred = true;
+        self
+    }
+
+    /// Name of the struct field or function argument.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn type_id(&self) -> &TypeId {
+        &self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn kind(&self) -> &DependencyKind {
+        &self.kind
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.deferred
+    }
+}
+
+impl Display for DependencyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyKind::Qualified(name) => write!(f, "qualifier={}", name),
+            DependencyKind::Primary => write!(f, "primary"),
+            DependencyKind::All => write!(f, "all"),
+        }
+    }
+}
+
+impl Display for Dependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let optional = if self.optional { ", optional" } else { "" };
+        let deferred = if self.deferred { ", deferred" } else { "" };
+        write!(f, "Dependency(field={}, type={}, {}{}{})", &self.field, self.type_name, &self.kind, optional, deferred)
+    }
+}
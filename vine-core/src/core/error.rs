@@ -0,0 +1,218 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+use config::ConfigError;
+
+/// The error type of vine.
+///
+/// Every failure of the framework is reported through one of the variants below, so callers can
+/// match on the failure kind. `Error` implements [`std::error::Error`] and therefore converts into
+/// `Box<dyn std::error::Error + Send + Sync>` or `anyhow::Error` with `?`.
+///
+/// Plain messages can still be turned into an error with `Error::from("...")`.
+#[derive(Debug)]
+pub enum Error {
+    /// No BeanDef matches the requested name (or type, when `name` is None).
+    BeanNotFound { name: Option<String>, ty: String, context: String },
+    /// The BeanDef exists but cannot be used, e.g. its conditions are not matched or its scope is unknown.
+    BeanUnavailable { name: String, ty: String, context: String, reason: String },
+    /// Several BeanDefs are assignable to the requested type and none or more than one of them is primary.
+    AmbiguousBean { ty: String, context: String, candidates: Vec<String>, primaries: usize },
+    /// A BeanDef with the same name is already registered.
+    DuplicateBean { name: String, ty: String, context: String },
+    /// A Scope with the same name is already registered.
+    DuplicateScope { name: String, context: String },
+    /// A child Context with the same name is already added.
+    DuplicateContext { name: String, context: String },
+    /// An init fn with the same name is already added.
+    DuplicateInitFn { name: String, context: String },
+    /// A bean cannot be downcast to the requested type.
+    DowncastFailed { from: String, to: String, reason: &'static str },
+    /// A bean depends on itself, `path` ends with the bean which closes the cycle.
+    CircularDependency { path: Vec<String>, context: String },
+    /// Creating a bean failed, `path` holds the names of the beans which led to it.
+    BeanCreation { name: String, path: Vec<String>, context: String, source: Box<Error> },
+    /// A property without default value is missing.
+    PropertyMissing { key: String },
+    /// A property value cannot be parsed into the requested type.
+    PropertyParse { value: String, ty: &'static str, source: Box<dyn StdError + Send + Sync> },
+    /// A runner has failed or could not be started.
+    RunnerFailed { runner: String, source: Box<dyn StdError + Send + Sync> },
+    /// The configuration cannot be built.
+    Config(ConfigError),
+    /// A bean factory or an event listener has returned an error of its own type.
+    Factory(Box<dyn StdError + Send + Sync>),
+    /// Several independent failures, e.g. of multiple runners.
+    Multiple(Vec<Error>),
+    /// Any other failure described by a message.
+    Other(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BeanNotFound { name: Some(name), ty, context } => {
+                write!(f, "cannot resolve Bean(name={}, type={}) in {}", name, ty, context)
+            },
+            Error::BeanNotFound { name: None, ty, context } => {
+                write!(f, "cannot resolve primary Bean(type={}) in {}: no candidates found", ty, context)
+            },
+            Error::BeanUnavailable { name, ty, context, reason } => {
+                write!(f, "cannot resolve Bean(name={}, type={}) in {}: {}", name, ty, context, reason)
+            },
+            Error::AmbiguousBean { ty, context, candidates, primaries } => {
+                let reason = if *primaries == 0 {
+                    "none of them is marked as primary"
+                } else {
+                    "more than one of them is marked as primary"
+                };
+                write!(f, "cannot resolve primary Bean(type={}) in {}: found {} candidates [{}] but {}", ty, context, candidates.len(), candidates.join(", "), reason)
+            },
+            Error::DuplicateBean { name, ty, context } => {
+                write!(f, "failed to register duplicated BeanDef(name={}, type={}) in {}", name, ty, context)
+            },
+            Error::DuplicateScope { name, context } => {
+                write!(f, "failed to register duplicated Scope(name={}) in {}", name, context)
+            },
+            Error::DuplicateContext { name, context } => {
+                write!(f, "failed to add duplicated Context(name={}) to {}", name, context)
+            },
+            Error::DuplicateInitFn { name, context } => {
+                write!(f, "failed to add duplicated init fn {:?} to {}", name, context)
+            },
+            Error::DowncastFailed { from, to, reason } => {
+                write!(f, "cannot downcast {} to {}: {}", from, to, reason)
+            },
+            Error::CircularDependency { path, context } => {
+                write!(f, "circular dependency detected: {} in {}", path.join(" -> "), context)
+            },
+            Error::BeanCreation { name, path, context, source } => {
+                write!(f, "failed to create Bean(name={}) ({}) in {}: {}", name, path.join(" -> "), context, source)
+            },
+            Error::PropertyMissing { key } => {
+                write!(f, "Property '{}' not found and no default value provided", key)
+            },
+            Error::PropertyParse { value, ty, source } => {
+                write!(f, "failed to parse {:?} as {}: {}", value, ty, source)
+            },
+            Error::RunnerFailed { runner, source } => {
+                write!(f, "runner {} has failed: {}", runner, source)
+            },
+            Error::Config(error) => write!(f, "configuration error: {}", error),
+            Error::Factory(error) => write!(f, "{}", error),
+            Error::Multiple(errors) => {
+                let errors: Vec<_> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", errors.join("\n"))
+            },
+            Error::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error {
+    /// Converts the error of a fallible bean factory. A vine `Error` is kept as it is, any other
+    /// error is kept as the source of [`Error::Factory`]; the Context attaches the bean name when
+    /// wrapping it into [`Error::BeanCreation`].
+    pub fn from_factory<E: Into<Box<dyn StdError + Send + Sync>>>(error: E) -> Error {
+        match error.into().downcast::<Error>() {
+            Ok(error) => *error,
+            Err(error) => Error::Factory(error),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::BeanCreation { source, .. } => Some(source.as_ref()),
+            Error::PropertyParse { source, .. } => Some(source.as_ref()),
+            Error::RunnerFailed { source, .. } => Some(source.as_ref()),
+            Error::Config(error) => Some(error),
+            Error::Factory(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(error: ConfigError) -> Self {
+        Error::Config(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as StdError;
+
+    use crate::core::Error;
+
+    #[test]
+    fn should_expose_source_of_bean_creation_error() {
+        let error = Error::BeanCreation {
+            name: "b".to_string(),
+            path: vec!["a".to_string(), "b".to_string()],
+            context: "Context(name=root)".to_string(),
+            source: Box::new(Error::PropertyMissing { key: "server.port".to_string() }),
+        };
+
+        assert_eq!(error.to_string(), "failed to create Bean(name=b) (a -> b) in Context(name=root): Property 'server.port' not found and no default value provided");
+        assert!(matches!(error.source().and_then(|e| e.downcast_ref::<Error>()), Some(Error::PropertyMissing { .. })));
+    }
+
+    #[test]
+    fn should_keep_vine_errors_of_factories() {
+        assert!(matches!(Error::from_factory(Error::PropertyMissing { key: "k".to_string() }), Error::PropertyMissing { .. }));
+        assert!(matches!(Error::from_factory("boom"), Error::Factory(error) if error.to_string() == "boom"));
+    }
+
+    #[test]
+    fn should_keep_sources_of_factory_errors() {
+        let error = Error::BeanCreation {
+            name: "b".to_string(),
+            path: vec!["b".to_string()],
+            context: "Context(name=root)".to_string(),
+            source: Box::new(Error::from_factory(std::fmt::Error)),
+        };
+
+        let factory_error = error.source().expect("BeanCreation has a source");
+        assert_eq!(factory_error.to_string(), "an error occurred when formatting an argument");
+        assert!(factory_error.source().is_some_and(|source| source.is::<std::fmt::Error>()));
+    }
+
+    #[test]
+    fn should_convert_into_boxed_std_error() {
+        fn fails() -> Result<(), Box<dyn StdError + Send + Sync>> {
+            Err(Error::from("boom"))?
+        }
+
+        assert_eq!(fails().unwrap_err().to_string(), "boom");
+    }
+
+    #[test]
+    fn should_convert_into_anyhow_error_with_sources() {
+        fn fails() -> anyhow::Result<()> {
+            Err(Error::PropertyParse {
+                value: "many".to_string(),
+                ty: "usize",
+                source: Box::new("many".parse::<usize>().unwrap_err()),
+            })?
+        }
+
+        let error = fails().unwrap_err();
+        assert!(matches!(error.downcast_ref::<Error>(), Some(Error::PropertyParse { ty: "usize", .. })));
+        assert_eq!(error.chain().count(), 2);
+        assert!(error.chain().last().is_some_and(|source| source.is::<std::num::ParseIntError>()));
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use linkme::distributed_slice;
+use log::{trace, warn};
+use tokio::runtime::Handle;
+
+use crate::context::auto_register_context::{NAMED_SETUP, Setup};
+use crate::context::context::Context;
+use crate::context::registration::Register;
+use crate::core::Error;
+use crate::core::bean_def::BeanDef;
+use crate::core::ty::Type;
+
+pub const APPLICATION_EVENT_PUBLISHER_BEAN: &str = "applicationEventPublisher";
+
+/// Receives the events of type `E` published through the [`ApplicationEventPublisher`].
+///
+/// Listeners are beans, usually declared with `#[event_listener]`, and are called in the order
+/// of [`Context::get_beans`](crate::context::context::Context::get_beans).
+pub trait EventListener<E> {
+    fn on_event(&self, event: &E) -> Result<(), Error>;
+}
+
+/// Async variant of [`EventListener`], called after the sync listeners of the event, see
+/// [`ApplicationEventPublisher::publish`] for their delivery.
+#[async_trait]
+pub trait AsyncEventListener<E: Sync> {
+    async fn on_event(&self, event: &E) -> Result<(), Error>;
+}
+
+/// Published by `App::exec` once the singletons have been created and the init fns have run.
+#[derive(Clone, Debug)]
+pub struct ContextInitialized;
+
+/// Published by `App::exec` once every Runner has been started.
+#[derive(Clone, Debug)]
+pub struct ApplicationStarted;
+
+/// Published by `App::exec` when a shutdown is requested or every Runner has finished, before
+/// the beans are destroyed.
+#[derive(Clone, Debug)]
+pub struct ApplicationStopping;
+
+/// Delivers events to the listeners of a Context, available as the `applicationEventPublisher`
+/// bean.
+///
+/// Every listener receives the event even if a previous one has failed, the failures are
+/// reported at once.
+pub struct ApplicationEventPublisher {
+    context: Context,
+}
+
+impl ApplicationEventPublisher {
+    pub fn new(context: &Context) -> ApplicationEventPublisher {
+        ApplicationEventPublisher { context: context.detached() }
+    }
+
+    /// Delivers the event to the sync listeners, then hands it over to the async ones in a task
+    /// spawned on the current tokio runtime without waiting for them. Their failures are only
+    /// logged, and publishing to async listeners outside a runtime fails.
+    pub fn publish<E: Send + Sync + 'static>(&self, event: E) -> Result<(), Error> {
+        let mut errors = self.deliver(&event);
+        match self.context.get_beans::<dyn AsyncEventListener<E> + Send + Sync>() {
+            Ok(listeners) if listeners.is_empty() => {},
+            Ok(listeners) => match Handle::try_current() {
+                Ok(handle) => {
+                    trace!("publishing {} to {} async listeners in background", std::any::type_name::<E>(), listeners.len());
+                    handle.spawn(async move { deliver_async(&listeners, &event).await });
+                },
+                Err(_) => errors.push(Error::from(format!(
+                    "cannot deliver {} to async listeners outside a tokio runtime, use publish_async",
+                    std::any::type_name::<E>(),
+                ))),
+            },
+            Err(error) => errors.push(error),
+        }
+        into_result(errors)
+    }
+
+    /// Delivers the event and waits until every listener, async ones included, has handled it.
+    pub async fn publish_async<E: Send + Sync + 'static>(&self, event: E) -> Result<(), Error> {
+        let mut errors = self.deliver(&event);
+        match self.context.get_beans::<dyn AsyncEventListener<E> + Send + Sync>() {
+            Ok(listeners) => errors.extend(deliver_async(&listeners, &event).await),
+            Err(error) => errors.push(error),
+        }
+        into_result(errors)
+    }
+
+    fn deliver<E: 'static>(&self, event: &E) -> Vec<Error> {
+        let listeners = match self.context.get_beans::<dyn EventListener<E> + Send + Sync>() {
+            Ok(listeners) => listeners,
+            Err(error) => return vec![error],
+        };
+
+        trace!("publishing {} to {} listeners", std::any::type_name::<E>(), listeners.len());
+        listeners.iter()
+            .filter_map(|listener| listener.on_event(event).err())
+            .inspect(|error| warn!("failed to handle {}: {}", std::any::type_name::<E>(), error))
+            .collect()
+    }
+}
+
+async fn deliver_async<E: Sync + 'static>(listeners: &[Arc<dyn AsyncEventListener<E> + Send + Sync>], event: &E) -> Vec<Error> {
+    let mut errors = Vec::new();
+    for listener in listeners {
+        if let Err(error) = listener.on_event(event).await {
+            warn!("failed to handle {}: {}", std::any::type_name::<E>(), &error);
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+/// Reports the failures of several listeners at once, also used by the code generated for
+/// `#[event_listener]`.
+pub fn into_result(mut errors: Vec<Error>) -> Result<(), Error> {
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.pop().unwrap()),
+        _ => Err(Error::Multiple(errors)),
+    }
+}
+
+impl Register for ApplicationEventPublisher {
+    fn register(ctx: &Context) -> Result<(), Error> {
+        let ty = Type::of::<ApplicationEventPublisher>();
+        ty.add_downcast::<ApplicationEventPublisher>(Arc::downcast::<ApplicationEventPublisher>);
+        ctx.register(BeanDef::builder()
+            .name(APPLICATION_EVENT_PUBLISHER_BEAN)
+            .ty(ty)
+            .get(Arc::new(|ctx| Ok(Arc::new(ApplicationEventPublisher::new(ctx)))))
+            .build())
+    }
+}
+
+#[distributed_slice(NAMED_SETUP)]
+static SETUP_APPLICATION_EVENT_PUBLISHER: Setup = Setup { name: APPLICATION_EVENT_PUBLISHER_BEAN, setup: ApplicationEventPublisher::register };
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use tokio::sync::Notify;
+
+    use crate::context::context::Context;
+    use crate::core::bean_def::BeanDef;
+    use crate::core::Error;
+    use crate::core::event::{ApplicationEventPublisher, AsyncEventListener, EventListener};
+    use crate::core::ty::Type;
+
+    struct Greeted(&'static str);
+
+    struct Recorder { calls: Mutex<Vec<String>>, delivered: Notify }
+
+    impl EventListener<Greeted> for Recorder {
+        fn on_event(&self, event: &Greeted) -> Result<(), Error> {
+            self.calls.lock().unwrap().push(format!("sync {}", event.0));
+            match event.0 {
+                "fail" => Err(Error::from("sync listener failed")),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AsyncEventListener<Greeted> for Recorder {
+        async fn on_event(&self, event: &Greeted) -> Result<(), Error> {
+            self.calls.lock().unwrap().push(format!("async {}", event.0));
+            self.delivered.notify_one();
+            Ok(())
+        }
+    }
+
+    fn register_recorder(ctx: &Context) -> Result<Arc<Recorder>, Error> {
+        let recorder = Arc::new(Recorder { calls: Default::default(), delivered: Notify::new() });
+        let ty = Type::of::<Recorder>();
+        ty.add_downcast::<dyn EventListener<Greeted> + Send + Sync>(|b| Ok(Arc::downcast::<Recorder>(b)?));
+        ty.add_downcast::<dyn AsyncEventListener<Greeted> + Send + Sync>(|b| Ok(Arc::downcast::<Recorder>(b)?));
+        let bean = recorder.clone();
+        ctx.register(BeanDef::builder()
+            .name("recorder")
+            .ty(ty)
+            .get(Arc::new(move |_| Ok(bean.clone())))
+            .build())?;
+        Ok(recorder)
+    }
+
+    #[tokio::test]
+    async fn should_deliver_events_to_async_listeners_in_background() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let recorder = register_recorder(&ctx)?;
+        let publisher = ApplicationEventPublisher::new(&ctx);
+
+        publisher.publish(Greeted("hello"))?;
+        assert_eq!(*recorder.calls.lock().unwrap(), vec!["sync hello"]);
+        recorder.delivered.notified().await;
+        assert_eq!(*recorder.calls.lock().unwrap(), vec!["sync hello", "async hello"]);
+
+        assert!(publisher.publish(Greeted("fail")).is_err());
+        recorder.delivered.notified().await;
+        assert_eq!(*recorder.calls.lock().unwrap(), vec!["sync hello", "async hello", "sync fail", "async fail"]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_fail_to_deliver_events_to_async_listeners_without_runtime() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let recorder = register_recorder(&ctx)?;
+
+        assert!(ApplicationEventPublisher::new(&ctx).publish(Greeted("hello")).is_err());
+        assert_eq!(*recorder.calls.lock().unwrap(), vec!["sync hello"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_publish_events_asynchronously() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let recorder = register_recorder(&ctx)?;
+
+        ApplicationEventPublisher::new(&ctx).publish_async(Greeted("hello")).await?;
+        assert_eq!(*recorder.calls.lock().unwrap(), vec!["sync hello", "async hello"]);
+        Ok(())
+    }
+}
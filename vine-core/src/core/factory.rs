@@ -0,0 +1,102 @@
+//! Conversion of the errors returned by fallible `#[bean]` factories and `#[event_listener]`
+//! methods into an [`Error`], used by the generated code.
+//!
+//! The conversion is picked from the concrete error type at the call site:
+//! - errors which convert into [`Error`], e.g. with `impl From<MyError> for Error`, use their
+//!   own conversion;
+//! - other `std::error::Error`s are kept as the source of [`Error::Factory`];
+//! - errors which only implement `Display` become [`Error::Other`] with their message.
+//!
+//! It relies on method resolution trying `&&&FactoryError<E>` first, then `&&FactoryError<E>` and
+//! `&FactoryError<E>`, so the traits must be in scope:
+//! ```ignore
+//! use vine::vine_core::core::factory::{DisplayIntoError as _, IntoError as _, StdErrorIntoError as _};
+//! let error: Error = (&&&FactoryError::new(error)).into_vine_error();
+//! ```
+use std::cell::Cell;
+use std::error::Error as StdError;
+use std::fmt::Display;
+
+use crate::core::Error;
+
+/// Error of a factory waiting to be converted by one of the traits of this module.
+pub struct FactoryError<E>(Cell<Option<E>>);
+
+impl<E> FactoryError<E> {
+    pub fn new(error: E) -> FactoryError<E> {
+        FactoryError(Cell::new(Some(error)))
+    }
+
+    fn take(&self) -> E {
+        self.0.take().expect("a FactoryError is converted only once")
+    }
+}
+
+pub trait IntoError {
+    fn into_vine_error(self) -> Error;
+}
+
+impl<E: Into<Error>> IntoError for &&&FactoryError<E> {
+    fn into_vine_error(self) -> Error {
+        self.take().into()
+    }
+}
+
+pub trait StdErrorIntoError {
+    fn into_vine_error(self) -> Error;
+}
+
+impl<E: Into<Box<dyn StdError + Send + Sync>>> StdErrorIntoError for &&FactoryError<E> {
+    fn into_vine_error(self) -> Error {
+        Error::from_factory(self.take())
+    }
+}
+
+pub trait DisplayIntoError {
+    fn into_vine_error(self) -> Error;
+}
+
+impl<E: Display> DisplayIntoError for &FactoryError<E> {
+    fn into_vine_error(self) -> Error {
+        Error::Other(self.take().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter};
+
+    use crate::core::Error;
+    use crate::core::factory::{DisplayIntoError as _, FactoryError, IntoError as _, StdErrorIntoError as _};
+
+    struct Unavailable;
+    impl From<Unavailable> for Error {
+        fn from(_: Unavailable) -> Self {
+            Error::PropertyMissing { key: "db.url".to_string() }
+        }
+    }
+
+    struct Message;
+    impl Display for Message {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "message")
+        }
+    }
+
+    #[test]
+    // the borrows select the conversion
+    #[allow(clippy::needless_borrow)]
+    fn should_convert_errors_of_factories() {
+        let error = (&&&FactoryError::new(Error::from("boom"))).into_vine_error();
+        assert!(matches!(error, Error::Other(message) if message == "boom"));
+
+        let error = (&&&FactoryError::new(Unavailable)).into_vine_error();
+        assert!(matches!(error, Error::PropertyMissing { .. }));
+
+        let error = (&&&FactoryError::new(std::fmt::Error)).into_vine_error();
+        assert!(matches!(error, Error::Factory(source) if source.is::<std::fmt::Error>()));
+
+        let error = (&&&FactoryError::new(Message)).into_vine_error();
+        assert!(matches!(error, Error::Other(message) if message == "message"));
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::context::context::{Context, InitContextFn};
+use crate::core::Error;
+use crate::core::order::LOWEST_PRECEDENCE;
+
+/// Property holding the list of init fns which are not run.
+pub const INIT_EXCLUDE_PROPERTY: &str = "vine.init.exclude";
+
+/// A named function run by `Context::init_contexts`, e.g. registering the routes of a
+/// `#[controller]`.
+///
+/// Init fns run in topological order of their `before`/`after` constraints, ties are broken by
+/// their order and then by name. Constraints on unknown or excluded init fns are ignored.
+pub struct InitFnDef {
+    name: String,
+    order: i32,
+    before: Vec<String>,
+    after: Vec<String>,
+    init_fn: InitContextFn,
+}
+
+pub struct InitFnDefBuilder {
+    name: Option<String>,
+    order: i32,
+    before: Vec<String>,
+    after: Vec<String>,
+    init_fn: Option<InitContextFn>,
+}
+
+impl InitFnDef {
+    pub fn builder() -> InitFnDefBuilder {
+        InitFnDefBuilder {
+            name: None,
+            order: LOWEST_PRECEDENCE,
+            before: Vec::new(),
+            after: Vec::new(),
+            init_fn: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+
+    /// Names of the init fns which run after this one.
+    pub fn before(&self) -> &[String] {
+        &self.before
+    }
+
+    /// Names of the init fns which run before this one.
+    pub fn after(&self) -> &[String] {
+        &self.after
+    }
+
+    pub fn call(&self, context: &Context) -> Result<(), Error> {
+        (self.init_fn)(context)
+    }
+}
+
+impl Display for InitFnDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InitFnDef(name={})", &self.name)
+    }
+}
+
+impl InitFnDefBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn before(mut self, name: &str) -> Self {
+        self.before.push(name.to_string());
+        self
+    }
+
+    pub fn after(mut self, name: &str) -> Self {
+        self.after.push(name.to_string());
+        self
+    }
+
+    pub fn init_fn(mut self, init_fn: InitContextFn) -> Self {
+        self.init_fn = Some(init_fn);
+        self
+    }
+
+    pub fn build(self) -> InitFnDef {
+        InitFnDef {
+            name: self.name.expect("InitFnDef requires a name"),
+            order: self.order,
+            before: self.before,
+            after: self.after,
+            init_fn: self.init_fn.expect("InitFnDef requires an init fn"),
+        }
+    }
+}
+
+/// Sorts the init fns in topological order of their constraints, ties are broken by order and
+/// name. Fails with the names forming a cycle, the first one being repeated at the end.
+pub(crate) fn sort_init_fns(init_fns: Vec<Arc<InitFnDef>>) -> Result<Vec<Arc<InitFnDef>>, Vec<String>> {
+    let index: HashMap<&str, usize> = init_fns.iter().enumerate()
+        .map(|(position, init_fn)| (init_fn.name(), position))
+        .collect();
+
+    let mut successors = vec![Vec::new(); init_fns.len()];
+    let mut predecessors = vec![Vec::new(); init_fns.len()];
+    for (position, init_fn) in init_fns.iter().enumerate() {
+        let before = init_fn.before().iter().filter_map(|name| index.get(name.as_str())).map(|&other| (position, other));
+        let after = init_fn.after().iter().filter_map(|name| index.get(name.as_str())).map(|&other| (other, position));
+        for (first, second) in before.chain(after) {
+            successors[first].push(second);
+            predecessors[second].push(first);
+        }
+    }
+
+    let mut pending: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut ready: BTreeSet<_> = init_fns.iter().enumerate()
+        .filter(|(position, _)| pending[*position] == 0)
+        .map(|(position, init_fn)| (init_fn.order(), init_fn.name(), position))
+        .collect();
+
+    let mut sorted = Vec::with_capacity(init_fns.len());
+    let mut done = vec![false; init_fns.len()];
+    while let Some((_, _, position)) = ready.pop_first() {
+        sorted.push(position);
+        done[position] = true;
+        for &successor in &successors[position] {
+            pending[successor] -= 1;
+            if pending[successor] == 0 {
+                ready.insert((init_fns[successor].order(), init_fns[successor].name(), successor));
+            }
+        }
+    }
+
+    if sorted.len() < init_fns.len() {
+        // every remaining init fn waits for another remaining one, walking backwards closes a cycle
+        let mut walk = vec![done.iter().position(|done| !done).unwrap()];
+        loop {
+            let last = *walk.last().unwrap();
+            let predecessor = *predecessors[last].iter().find(|&&predecessor| !done[predecessor]).unwrap();
+            if let Some(start) = walk.iter().position(|&position| position == predecessor) {
+                walk.push(predecessor);
+                return Err(walk[start..].iter().rev().map(|&position| init_fns[position].name().to_string()).collect());
+            }
+            walk.push(predecessor);
+        }
+    }
+
+    Ok(sorted.into_iter().map(|position| init_fns[position].clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::init_fn_def::{InitFnDef, InitFnDefBuilder, sort_init_fns};
+
+    fn init_fn(name: &str) -> InitFnDefBuilder {
+        InitFnDef::builder().name(name).init_fn(Arc::new(|_| Ok(())))
+    }
+
+    fn sorted_names(init_fns: Vec<InitFnDefBuilder>) -> Result<Vec<String>, Vec<String>> {
+        let init_fns = init_fns.into_iter().map(|builder| Arc::new(builder.build())).collect();
+        Ok(sort_init_fns(init_fns)?.iter().map(|init_fn| init_fn.name().to_string()).collect())
+    }
+
+    #[test]
+    fn should_sort_init_fns_by_constraints_then_order_and_name() {
+        let names = sorted_names(vec![
+            init_fn("d"),
+            init_fn("c").after("routes"),
+            init_fn("routes").after("missing"),
+            init_fn("b").order(1),
+            init_fn("a").order(1).before("routes"),
+            init_fn("first").order(0),
+        ]);
+
+        assert_eq!(names, Ok(vec!["first", "a", "b", "d", "routes", "c"].into_iter().map(String::from).collect()));
+    }
+
+    #[test]
+    fn should_report_cycles() {
+        let names = sorted_names(vec![
+            init_fn("a").before("b"),
+            init_fn("b").before("c"),
+            init_fn("c").before("b"),
+            init_fn("d").after("c"),
+        ]);
+
+        assert_eq!(names, Err(vec!["b".to_string(), "c".to_string(), "b".to_string()]));
+    }
+}
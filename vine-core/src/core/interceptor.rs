@@ -0,0 +1,400 @@
+use std::any::{type_name, TypeId};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{debug, info, trace, warn};
+
+use crate::context::context::Context;
+use crate::core::{DynBean, Error};
+use crate::core::bean_def::BeanDef;
+use crate::core::dependency::Dependency;
+use crate::core::post_processor::BeanPostProcessor;
+use crate::core::ty::Type;
+
+pub type Interceptors = Arc<[Arc<dyn MethodInterceptor + Send + Sync>]>;
+
+/// Wraps a bean named by the second argument into a proxy running the interceptors.
+pub type ProxyFn = fn(DynBean, &str, &Interceptors) -> Result<DynBean, Error>;
+
+/// Cross-cutting behaviour run around every method call of the beans registered with
+/// `#[injectable(intercept)]`, e.g. timing, logging or retries.
+///
+/// Interceptors are beans, discovered through `#[injectable]`, and run as a chain in the order
+/// of [`Context::get_beans`](crate::context::context::Context::get_beans), after the interceptors of the
+/// method attributes (`#[timed]`, `#[logged]` and `#[retry]`). Each one calls
+/// [`MethodInvocation::proceed`] to continue with the next interceptor and eventually the
+/// intercepted method. As the caller needs a result, the rest of the chain is run right after an
+/// interceptor which returns without proceeding.
+///
+/// Interceptors are not post-processed, so they can be looked up before or while the proxies
+/// running them are created.
+/// ```ignore
+/// #[injectable]
+/// impl MethodInterceptor for Timing {
+///     fn intercept(&self, invocation: &mut MethodInvocation) {
+///         invocation.proceed();
+///         info!("{} took {:?}", invocation, invocation.elapsed());
+///     }
+/// }
+/// ```
+pub trait MethodInterceptor {
+    fn intercept(&self, invocation: &mut MethodInvocation);
+}
+
+/// The interceptors of a bean, held by its proxy generated for `#[injectable(intercept)]`.
+pub struct Interception {
+    bean: String,
+    ty: &'static str,
+    interceptors: Interceptors,
+}
+
+impl Interception {
+    pub fn new(bean: &str, ty: &'static str, interceptors: Interceptors) -> Interception {
+        Interception { bean: bean.to_string(), ty, interceptors }
+    }
+
+    /// Runs the interceptors of the method followed by the interceptors of the bean around
+    /// `target`, and returns the result of its last call. `is_failed` tells whether a result
+    /// is a failure, e.g. an `Err`.
+    pub fn invoke<R>(
+        &self,
+        method: &'static str,
+        args: &[&dyn Debug],
+        method_interceptors: &[&dyn MethodInterceptor],
+        mut target: impl FnMut() -> R,
+        is_failed: fn(&R) -> bool,
+    ) -> R {
+        let mut result = None;
+        let mut call = || {
+            let returned = target();
+            let failed = is_failed(&returned);
+            result = Some(returned);
+            failed
+        };
+        MethodInvocation {
+            bean: &self.bean,
+            ty: self.ty,
+            method,
+            args,
+            method_interceptors,
+            interceptors: &self.interceptors,
+            target: &mut call,
+            position: 0,
+            calls: 0,
+            elapsed: Duration::ZERO,
+            failed: false,
+        }.proceed();
+
+        // proceeding always reaches the target, which is called again rather than panicking
+        match result {
+            Some(result) => result,
+            None => target(),
+        }
+    }
+}
+
+/// A call of an intercepted method, passed along the chain of [`MethodInterceptor`]s.
+pub struct MethodInvocation<'a> {
+    bean: &'a str,
+    ty: &'static str,
+    method: &'static str,
+    args: &'a [&'a dyn Debug],
+    method_interceptors: &'a [&'a dyn MethodInterceptor],
+    interceptors: &'a [Arc<dyn MethodInterceptor + Send + Sync>],
+    target: &'a mut dyn FnMut() -> bool,
+    position: usize,
+    calls: usize,
+    elapsed: Duration,
+    failed: bool,
+}
+
+impl<'a> MethodInvocation<'a> {
+    /// Name of the intercepted bean.
+    pub fn bean(&self) -> &str {
+        self.bean
+    }
+
+    /// Name of the intercepted trait.
+    pub fn ty(&self) -> &'static str {
+        self.ty
+    }
+
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    pub fn args(&self) -> &[&'a dyn Debug] {
+        self.args
+    }
+
+    /// Time spent in the last [`proceed`](MethodInvocation::proceed) call.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Whether the method returned an `Err` in the last [`proceed`](MethodInvocation::proceed) call.
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Runs the rest of the chain and the intercepted method. May be called again, e.g. to retry
+    /// a failed call, the result of the last call is returned to the caller.
+    pub fn proceed(&mut self) {
+        let started_at = Instant::now();
+        match self.interceptor(self.position) {
+            Some(interceptor) => {
+                let calls = self.calls;
+                self.position += 1;
+                interceptor.intercept(self);
+                if self.calls == calls {
+                    debug!("an interceptor of {} did not proceed, running the rest of the chain", self);
+                    self.proceed();
+                }
+                self.position -= 1;
+            },
+            None => {
+                self.calls += 1;
+                self.failed = (self.target)();
+            },
+        }
+        self.elapsed = started_at.elapsed();
+    }
+
+    fn interceptor(&self, position: usize) -> Option<&'a dyn MethodInterceptor> {
+        let method_interceptors: &'a [&'a dyn MethodInterceptor] = self.method_interceptors;
+        let interceptors: &'a [Arc<dyn MethodInterceptor + Send + Sync>] = self.interceptors;
+        match method_interceptors.get(position) {
+            Some(interceptor) => Some(*interceptor),
+            None => interceptors.get(position - method_interceptors.len()).map(|interceptor| interceptor.as_ref() as &dyn MethodInterceptor),
+        }
+    }
+}
+
+/// Interceptor of the methods marked with `#[timed]`, logs the time taken by each call.
+pub struct Timed;
+
+impl MethodInterceptor for Timed {
+    fn intercept(&self, invocation: &mut MethodInvocation) {
+        invocation.proceed();
+        info!("{} took {:?}", invocation, invocation.elapsed());
+    }
+}
+
+/// Interceptor of the methods marked with `#[logged]`, logs each call and whether it failed.
+pub struct Logged;
+
+impl MethodInterceptor for Logged {
+    fn intercept(&self, invocation: &mut MethodInvocation) {
+        info!("calling {}", invocation);
+        invocation.proceed();
+        match invocation.is_failed() {
+            true => warn!("{} failed", invocation),
+            false => info!("{} succeeded", invocation),
+        }
+    }
+}
+
+/// Interceptor of the methods marked with `#[retry(attempts = 3)]`, calls a failing method
+/// again until it succeeds or `attempts` calls have failed.
+pub struct Retry {
+    pub attempts: usize,
+}
+
+impl MethodInterceptor for Retry {
+    fn intercept(&self, invocation: &mut MethodInvocation) {
+        invocation.proceed();
+        for attempt in 2..=self.attempts {
+            if !invocation.is_failed() {
+                return;
+            }
+            warn!("{} failed, retrying ({}/{})", invocation, attempt, self.attempts);
+            invocation.proceed();
+        }
+    }
+}
+
+/// Registers within `context` the post-processor replacing the beans of type `T` by their proxy
+/// `P`, used by the code generated for `#[injectable(intercept)]`. Proxies of several traits of
+/// `T` wrap each other, and the proxy type delegates its other downcasts to the wrapped bean
+/// (see [`Type::add_delegate`]).
+///
+/// The post-processor is a bean named after `P`, registering the same proxy twice within a
+/// Context fails with [`Error::DuplicateBean`].
+pub fn register_proxy<T: 'static, P: 'static>(context: &Context, proxy_fn: ProxyFn) -> Result<(), Error> {
+    trace!("register proxy {} for {} in {}", type_name::<P>(), type_name::<T>(), context);
+    let ty = Type::of::<ProxyPostProcessor<T, P>>();
+    ty.add_downcast::<dyn BeanPostProcessor + Send + Sync>(|b| Ok(Arc::downcast::<ProxyPostProcessor<T, P>>(b)?));
+    context.register(BeanDef::builder()
+        .name(type_name::<P>())
+        .ty(ty)
+        .dependency(Dependency::all::<dyn MethodInterceptor + Send + Sync>("interceptors"))
+        .get(Arc::new(move |ctx| Ok(Arc::new(ProxyPostProcessor::<T, P> {
+            proxy_fn,
+            interceptors: ctx.get_beans::<dyn MethodInterceptor + Send + Sync>()?.into(),
+            marker: PhantomData,
+        }))))
+        .build())
+}
+
+/// Replaces the beans of type `T` by their proxy `P`.
+struct ProxyPostProcessor<T, P> {
+    proxy_fn: ProxyFn,
+    interceptors: Interceptors,
+    marker: PhantomData<fn() -> (T, P)>,
+}
+
+impl<T: 'static, P: 'static> BeanPostProcessor for ProxyPostProcessor<T, P> {
+    fn post_process(&self, name: &str, ty: &Type, bean: DynBean) -> Result<DynBean, Error> {
+        if ty.id() != &TypeId::of::<T>() {
+            return Ok(bean);
+        }
+
+        trace!("wrapping Bean(name={}) into {}", name, type_name::<P>());
+        (self.proxy_fn)(bean, name, &self.interceptors)
+    }
+}
+
+impl Display for MethodInvocation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}{:?} of {}", self.ty, self.method, self.args, self.bean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    struct Recording { name: &'static str, calls: Arc<Mutex<Vec<String>>> }
+    impl MethodInterceptor for Recording {
+        fn intercept(&self, invocation: &mut MethodInvocation) {
+            self.calls.lock().unwrap().push(format!("{} before {}", self.name, invocation));
+            invocation.proceed();
+            self.calls.lock().unwrap().push(format!("{} after failed={}", self.name, invocation.is_failed()));
+        }
+    }
+
+    struct Retrying;
+    impl MethodInterceptor for Retrying {
+        fn intercept(&self, invocation: &mut MethodInvocation) {
+            invocation.proceed();
+            if invocation.is_failed() {
+                invocation.proceed();
+            }
+        }
+    }
+
+    struct Skipping;
+    impl MethodInterceptor for Skipping {
+        fn intercept(&self, _invocation: &mut MethodInvocation) {}
+    }
+
+    #[test]
+    fn should_run_interceptors_around_target() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let interception = Interception::new("testBean", "TestTrait", Arc::new([
+            Arc::new(Recording { name: "outer", calls: calls.clone() }) as Arc<dyn MethodInterceptor + Send + Sync>,
+            Arc::new(Retrying),
+            Arc::new(Recording { name: "inner", calls: calls.clone() }),
+        ]));
+
+        let mut attempts = 0;
+        let result = interception.invoke("call", &[&1, &"two"], &[], || {
+            attempts += 1;
+            if attempts < 2 { Err(attempts) } else { Ok(attempts) }
+        }, Result::is_err);
+
+        assert_eq!(result, Ok(2));
+        assert_eq!(*calls.lock().unwrap(), vec![
+            r#"outer before TestTrait::call[1, "two"] of testBean"#,
+            r#"inner before TestTrait::call[1, "two"] of testBean"#,
+            "inner after failed=true",
+            r#"inner before TestTrait::call[1, "two"] of testBean"#,
+            "inner after failed=false",
+            "outer after failed=false",
+        ]);
+    }
+
+    #[test]
+    fn should_run_rest_of_chain_after_interceptors_which_do_not_proceed() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let interception = Interception::new("testBean", "TestTrait", Arc::new([
+            Arc::new(Skipping) as Arc<dyn MethodInterceptor + Send + Sync>,
+            Arc::new(Recording { name: "recording", calls: calls.clone() }),
+        ]));
+
+        assert_eq!(interception.invoke("call", &[], &[&Skipping], || 1, |_| false), 1);
+        assert_eq!(*calls.lock().unwrap(), vec![
+            "recording before TestTrait::call[] of testBean",
+            "recording after failed=false",
+        ]);
+    }
+
+    #[test]
+    fn should_run_method_interceptors_before_bean_interceptors() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let interception = Interception::new("testBean", "TestTrait", Arc::new([
+            Arc::new(Recording { name: "bean", calls: calls.clone() }) as Arc<dyn MethodInterceptor + Send + Sync>,
+        ]));
+
+        let mut attempts = 0;
+        let result = interception.invoke("call", &[], &[&Timed, &Logged, &Retry { attempts: 3 }], || {
+            attempts += 1;
+            Err::<(), _>(attempts)
+        }, Result::is_err);
+
+        assert_eq!(result, Err(3));
+        assert_eq!(calls.lock().unwrap().len(), 6);
+    }
+
+    trait Greeter { fn greet(&self, name: &str) -> String; }
+
+    struct TestGreeter;
+    impl Greeter for TestGreeter {
+        fn greet(&self, name: &str) -> String { format!("hello {}", name) }
+    }
+
+    struct GreeterProxy { inner: DynBean, target: Arc<TestGreeter>, interception: Interception }
+    impl Greeter for GreeterProxy {
+        fn greet(&self, name: &str) -> String {
+            self.interception.invoke("greet", &[&name], &[], || self.target.greet(name), |_| false)
+        }
+    }
+
+    #[test]
+    fn should_proxy_intercepted_beans() -> Result<(), Error> {
+        let ctx = Context::new("test-context");
+        let ty = Type::of::<TestGreeter>();
+        ty.add_downcast::<TestGreeter>(Arc::downcast::<TestGreeter>);
+        ty.add_downcast::<dyn Greeter + Send + Sync>(|b| Ok(Arc::downcast::<TestGreeter>(b)?));
+        ctx.register(BeanDef::builder().name("greeter").ty(ty).get(Arc::new(|_| Ok(Arc::new(TestGreeter)))).build())?;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recording = Arc::new(Recording { name: "recording", calls: calls.clone() });
+        let ty = Type::of::<Recording>();
+        ty.add_downcast::<dyn MethodInterceptor + Send + Sync>(|b| Ok(Arc::downcast::<Recording>(b)?));
+        ctx.register(BeanDef::builder().name("recording").ty(ty).get(Arc::new(move |_| Ok(recording.clone()))).build())?;
+
+        let ty = Type::of::<GreeterProxy>();
+        ty.add_downcast::<dyn Greeter + Send + Sync>(|b| Ok(Arc::downcast::<GreeterProxy>(b)?));
+        ty.add_delegate(|b| b.downcast_ref::<GreeterProxy>().expect("GreeterProxy").inner.clone());
+        register_proxy::<TestGreeter, GreeterProxy>(&ctx, |bean, name, interceptors| {
+            let target = Type::downcast::<TestGreeter>(bean.clone())?;
+            Ok(Arc::new(GreeterProxy { inner: bean, target, interception: Interception::new(name, "Greeter", interceptors.clone()) }))
+        })?;
+        let duplicate = register_proxy::<TestGreeter, GreeterProxy>(&ctx, |bean, _, _| Ok(bean));
+        assert!(matches!(duplicate, Err(Error::DuplicateBean { .. })));
+
+        let greeter = ctx.get_bean::<dyn Greeter + Send + Sync>("greeter")?;
+        assert_eq!(greeter.greet("vine"), "hello vine");
+        assert_eq!(*calls.lock().unwrap(), vec![
+            r#"recording before Greeter::greet["vine"] of greeter"#,
+            "recording after failed=false",
+        ]);
+        assert!(ctx.get_bean::<TestGreeter>("greeter").is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::any::TypeId;
+
+use async_trait::async_trait;
+use log::trace;
+
+use crate::core::{DynBean, Error};
+use crate::core::ty::Type;
+
+/// Callback invoked by the Context right after a bean has been created and its dependencies injected.
+///
+/// Register the implementation with `#[injectable]` so the Context can discover it:
+/// ```ignore
+/// #[injectable]
+/// impl InitializingBean for ConnectionPool {
+///     fn after_properties_set(&self) -> Result<(), Error> {
+///         self.connect()
+///     }
+/// }
+/// ```
+pub trait InitializingBean {
+    fn after_properties_set(&self) -> Result<(), Error>;
+}
+
+/// Async form of [`InitializingBean`].
+#[async_trait]
+pub trait AsyncInitializingBean {
+    async fn after_properties_set(&self) -> Result<(), Error>;
+}
+
+/// Callback invoked by the Context on shutdown. Beans are destroyed in reverse creation order.
+pub trait DisposableBean {
+    fn destroy(&self) -> Result<(), Error>;
+}
+
+/// Async form of [`DisposableBean`].
+#[async_trait]
+pub trait AsyncDisposableBean {
+    async fn destroy(&self) -> Result<(), Error>;
+}
+
+/// Whether the beans of the type have an [`AsyncInitializingBean`] callback, which is only run
+/// when they are created asynchronously.
+pub(crate) fn is_async_initialized(ty: &Type) -> bool {
+    ty.assignable(&TypeId::of::<dyn AsyncInitializingBean + Send + Sync>())
+}
+
+/// Runs the [`InitializingBean`] callback. Beans with an [`AsyncInitializingBean`] callback are
+/// rejected, they must be created with [`initialize_async`].
+pub(crate) fn initialize(ty: &Type, dyn_bean: &DynBean) -> Result<(), Error> {
+    if is_async_initialized(ty) {
+        return Err(Error::from(format!("{} is an AsyncInitializingBean, it must be created asynchronously", ty)));
+    }
+
+    if ty.assignable(&TypeId::of::<dyn InitializingBean + Send + Sync>()) {
+        trace!("calling InitializingBean::after_properties_set of {}", ty);
+        Type::downcast::<dyn InitializingBean + Send + Sync>(dyn_bean.clone())?.after_properties_set()?;
+    }
+
+    Ok(())
+}
+
+/// Same as [`initialize`], but also awaits the [`AsyncInitializingBean`] callback.
+pub(crate) async fn initialize_async(ty: &Type, dyn_bean: &DynBean) -> Result<(), Error> {
+    if ty.assignable(&TypeId::of::<dyn InitializingBean + Send + Sync>()) {
+        trace!("calling InitializingBean::after_properties_set of {}", ty);
+        Type::downcast::<dyn InitializingBean + Send + Sync>(dyn_bean.clone())?.after_properties_set()?;
+    }
+
+    if is_async_initialized(ty) {
+        trace!("calling AsyncInitializingBean::after_properties_set of {}", ty);
+        Type::downcast::<dyn AsyncInitializingBean + Send + Sync>(dyn_bean.clone())?.after_properties_set().await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn destroy(ty: &Type, dyn_bean: &DynBean) -> Result<(), Error> {
+    if ty.assignable(&TypeId::of::<dyn AsyncDisposableBean + Send + Sync>()) {
+        trace!("calling AsyncDisposableBean::destroy of {}", ty);
+        Type::downcast::<dyn AsyncDisposableBean + Send + Sync>(dyn_bean.clone())?.destroy().await?;
+    }
+
+    if ty.assignable(&TypeId::of::<dyn DisposableBean + Send + Sync>()) {
+        trace!("calling DisposableBean::destroy of {}", ty);
+        Type::downcast::<dyn DisposableBean + Send + Sync>(dyn_bean.clone())?.destroy()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use crate::core::{DynBean, Error};
+    use crate::core::lifecycle::{AsyncInitializingBean, initialize, initialize_async};
+    use crate::core::ty::Type;
+
+    struct AsyncTestBean { initialized: AtomicUsize }
+    #[async_trait]
+    impl AsyncInitializingBean for AsyncTestBean {
+        async fn after_properties_set(&self) -> Result<(), Error> {
+            tokio::task::yield_now().await;
+            self.initialized.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn async_test_bean() -> (Arc<Type>, Arc<AsyncTestBean>) {
+        let ty = Type::of::<AsyncTestBean>();
+        ty.add_downcast::<dyn AsyncInitializingBean + Send + Sync>(|b| Ok(Arc::downcast::<AsyncTestBean>(b)?));
+        (ty, Arc::new(AsyncTestBean { initialized: AtomicUsize::new(0) }))
+    }
+
+    #[tokio::test]
+    async fn should_await_async_callback() -> Result<(), Error> {
+        let (ty, bean) = async_test_bean();
+        let dyn_bean: DynBean = bean.clone();
+        initialize_async(&ty, &dyn_bean).await?;
+        assert_eq!(bean.initialized.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reject_async_callback_on_sync_path() {
+        let (ty, bean) = async_test_bean();
+        let dyn_bean: DynBean = bean.clone();
+        assert!(initialize(&ty, &dyn_bean).is_err());
+        assert_eq!(bean.initialized.load(Ordering::SeqCst), 0);
+    }
+}
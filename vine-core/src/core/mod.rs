@@ -4,6 +4,19 @@ use std::sync::Arc;
 pub mod ty;
 pub mod bean_def;
 pub mod runner;
+pub mod condition;
+pub mod dependency;
+pub mod scope;
+pub mod lifecycle;
+pub mod event;
+pub mod init_fn_def;
+pub mod interceptor;
+pub mod order;
+pub mod post_processor;
+pub mod shutdown;
+#[doc(hidden)]
+pub mod factory;
+mod error;
 
-pub type Error = String;
-pub(crate) type DynBean = Arc<dyn Any + Send + Sync>;
\ No newline at end of file
+pub use error::Error;
+pub type DynBean = Arc<dyn Any + Send + Sync>;
\ No newline at end of file
@@ -0,0 +1,17 @@
+/// Order of beans which do not declare one, they come after every ordered bean.
+pub const LOWEST_PRECEDENCE: i32 = i32::MAX;
+pub const HIGHEST_PRECEDENCE: i32 = i32::MIN;
+
+/// Orders a bean among the other candidates returned by `Context::get_beans`, lower values first.
+///
+/// Takes precedence over the order of the BeanDef (`#[order(n)]`). Register the implementation
+/// with `#[injectable]` so the Context can discover it:
+/// ```ignore
+/// #[injectable]
+/// impl Ordered for AuthFilter {
+///     fn order(&self) -> i32 { 10 }
+/// }
+/// ```
+pub trait Ordered {
+    fn order(&self) -> i32;
+}
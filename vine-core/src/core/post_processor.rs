@@ -0,0 +1,26 @@
+use crate::core::{DynBean, Error};
+use crate::core::ty::Type;
+
+/// Hook applied by the Context to every created bean, after its initialization callbacks and
+/// before it is cached or returned. The returned bean replaces the created one, e.g. a proxy
+/// adding metrics or logging around it.
+///
+/// Post-processors are beans themselves, discovered through `#[injectable]`, and run in the
+/// order of [`Context::get_beans`](crate::context::context::Context::get_beans). They are created with
+/// their dependencies before any other bean, and are applied neither to each other nor to those
+/// dependencies, nor to the `config` bean used to evaluate their conditions, nor to
+/// [`MethodInterceptor`](crate::core::interceptor::MethodInterceptor)s. A replacement of another
+/// type must register the downcasts callers rely on, as the Context downcasts beans by their
+/// runtime type.
+/// ```ignore
+/// #[injectable]
+/// impl BeanPostProcessor for Metrics {
+///     fn post_process(&self, name: &str, ty: &Type, bean: DynBean) -> Result<DynBean, Error> {
+///         self.register(name, ty.name());
+///         Ok(bean)
+///     }
+/// }
+/// ```
+pub trait BeanPostProcessor {
+    fn post_process(&self, name: &str, ty: &Type, bean: DynBean) -> Result<DynBean, Error>;
+}
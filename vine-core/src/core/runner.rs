@@ -5,6 +5,7 @@ use config::Config;
 use log::debug;
 use tokio::runtime::Runtime;
 use crate::core::Error;
+use crate::core::shutdown::Shutdown;
 
 #[async_trait]
 pub trait Runner {
@@ -22,8 +23,22 @@ pub trait Runner {
             .thread_name(self.name().to_lowercase())
             .enable_all()
             .build()
-            .map_err(|_e| Error::from(format!("cannot initialize runtime for runner {}", self.name())))
+            .map_err(|e| Error::RunnerFailed { runner: self.name().to_string(), source: Box::new(e) })
     }
 
     async fn run(&self) -> Result<(), Error>;
+
+    /// Runs until the runner finishes or a shutdown is requested.
+    ///
+    /// By default `run` is cancelled on shutdown. Runners which are able to drain in-flight
+    /// work (e.g. a web server) should override it and stop gracefully.
+    async fn run_until_shutdown(&self, shutdown: Shutdown) -> Result<(), Error> where Self: Sync {
+        tokio::select! {
+            result = self.run() => result,
+            _ = shutdown.wait() => {
+                debug!("runner {} has been cancelled by shutdown", self.name());
+                Ok(())
+            },
+        }
+    }
 }
@@ -0,0 +1,35 @@
+use crate::core::{DynBean, Error};
+
+/// Beans of singleton scope are created once and cached by the Context. This is the default scope.
+pub const SINGLETON: &str = "singleton";
+
+/// Beans of prototype scope are created on every resolution and never cached.
+pub const PROTOTYPE: &str = "prototype";
+
+/// A custom bean scope which decides when a bean instance is created and how long it is reused.
+///
+/// Scopes are registered with [`Context::register_scope`](crate::context::context::Context::register_scope)
+/// and selected by name with `BeanDefBuilder::scope`.
+///
+/// # Examples
+/// ```ignore
+/// struct TaskScope;
+///
+/// impl Scope for TaskScope {
+///     fn name(&self) -> &str { "task" }
+///
+///     fn get(&self, bean_name: &str, create_fn: &dyn Fn() -> Result<DynBean, Error>) -> Result<DynBean, Error> {
+///         TASK_BEANS.with(|beans| match beans.get(bean_name) {
+///             Some(bean) => Ok(bean.clone()),
+///             None => create_fn(),
+///         })
+///     }
+/// }
+/// ```
+pub trait Scope {
+    /// Returns the name used to reference this scope from a BeanDef.
+    fn name(&self) -> &str;
+
+    /// Returns the bean with the given name from this scope, calling `create_fn` to create it when needed.
+    fn get(&self, bean_name: &str, create_fn: &dyn Fn() -> Result<DynBean, Error>) -> Result<DynBean, Error>;
+}
@@ -0,0 +1,93 @@
+use log::{debug, warn};
+use tokio::sync::watch;
+
+/// Token handed to every [`Runner`](crate::core::runner::Runner) which resolves once the application is shutting down.
+#[derive(Clone)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+/// The sending side of [`Shutdown`], owned by the App.
+pub struct ShutdownTrigger {
+    sender: watch::Sender<bool>,
+}
+
+impl Shutdown {
+    /// Returns true once a shutdown has been requested.
+    pub fn is_requested(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Waits until a shutdown is requested.
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        // an error means the trigger is gone, nothing can request a shutdown anymore
+        if receiver.wait_for(|requested| *requested).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl Default for ShutdownTrigger {
+    fn default() -> Self {
+        ShutdownTrigger { sender: watch::Sender::new(false) }
+    }
+}
+
+impl ShutdownTrigger {
+    pub fn subscribe(&self) -> Shutdown {
+        Shutdown { receiver: self.sender.subscribe() }
+    }
+
+    /// Requests a shutdown of every subscribed [`Shutdown`] token.
+    pub fn shutdown(&self) {
+        self.sender.send_replace(true);
+    }
+}
+
+/// Waits for SIGINT (Ctrl+C) or, on unix, SIGTERM.
+pub(crate) async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            warn!("cannot listen for SIGINT: {}", error);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => { signal.recv().await; },
+            Err(error) => {
+                warn!("cannot listen for SIGTERM: {}", error);
+                std::future::pending::<()>().await;
+            },
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => debug!("SIGINT received"),
+        _ = terminate => debug!("SIGTERM received"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::shutdown::ShutdownTrigger;
+
+    #[tokio::test]
+    async fn should_notify_every_subscriber() {
+        let trigger = ShutdownTrigger::default();
+        let first = trigger.subscribe();
+        let second = first.clone();
+        assert!(!first.is_requested());
+
+        trigger.shutdown();
+        first.wait().await;
+        second.wait().await;
+        assert!(second.is_requested());
+    }
+}
@@ -1,6 +1,6 @@
 use std::any::{Any, type_name, TypeId};
 use std::fmt::{Debug, Display, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 
 use dashmap::DashMap;
@@ -24,7 +24,9 @@ pub struct Type {
     /// Human-readable type name
     name: &'static str,
     /// Registry of downcast functions for converting to different types/traits
-    downcast_fns: Arc<DashMap<TypeId, Arc<dyn Any + Send + Sync>>>
+    downcast_fns: Arc<DashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    /// Unwraps a proxy to the bean it delegates unregistered downcasts to
+    delegate_fn: OnceLock<fn(&DynBean) -> DynBean>,
 }
 
 // Global registry of all registered types, indexed by TypeId for fast lookup
@@ -55,6 +57,7 @@ impl Type {
                 id: TypeId::of::<T>(),
                 name,
                 downcast_fns: Default::default(),
+                delegate_fn: OnceLock::new(),
             })
         });
 
@@ -99,11 +102,23 @@ impl Type {
     pub fn add_downcast<T: ?Sized + 'static>(&self, downcast_fn: fn(DynBean) -> Result<Arc<T>, DynBean>) {
         let alias_id = TypeId::of::<T>();
         trace!("register {} downcast fn for {}", self, type_name::<T>());
-        if let Some(_) = self.downcast_fns.insert(alias_id, Arc::new(downcast_fn)) {
+        if self.downcast_fns.insert(alias_id, Arc::new(downcast_fn)).is_some() {
             warn!("override {} downcast fn for {}", self, type_name::<T>());
         }
     }
 
+    /// Registers the function unwrapping a proxy of this type (e.g. a bean replaced by a
+    /// [`BeanPostProcessor`](crate::core::post_processor::BeanPostProcessor)) to the bean it wraps.
+    ///
+    /// Downcasts which are not registered on the proxy type are delegated to the wrapped bean,
+    /// so the proxy remains usable as the original type and its other traits.
+    pub fn add_delegate(&self, delegate_fn: fn(&DynBean) -> DynBean) {
+        trace!("register {} delegate fn", self);
+        if self.delegate_fn.set(delegate_fn).is_err() {
+            warn!("ignore {} delegate fn, it is already registered", self);
+        }
+    }
+
     /// Attempts to downcast a DynBean to the specified type T.
     /// 
     /// This method looks up the appropriate downcast function and applies it to convert
@@ -129,12 +144,23 @@ impl Type {
     pub fn downcast<T: ?Sized + 'static>(dyn_bean: DynBean) -> Result<Arc<T>, Error> {
         let type_id = dyn_bean.as_ref().type_id();
         let Some(type_ref) = TYPES.get(&type_id) else {
-            return Err(Error::from(format!("Type {:?} is not registered in vine Type System", type_id)))
+            return Err(Error::DowncastFailed {
+                from: format!("{:?}", type_id),
+                to: type_name::<T>().to_string(),
+                reason: "type is not registered in vine Type System",
+            })
         };
 
         let alias_id = TypeId::of::<T>();
         let Some(downcast_fn) = type_ref.value().downcast_fns.get(&alias_id) else {
-            return Err(Error::from(format!("No downcast function registered for {} -> {}", type_ref.value().name(), type_name::<T>())))
+            if let Some(delegate_fn) = type_ref.value().delegate_fn.get() {
+                return Type::downcast::<T>(delegate_fn(&dyn_bean));
+            }
+            return Err(Error::DowncastFailed {
+                from: type_ref.value().name().to_string(),
+                to: type_name::<T>().to_string(),
+                reason: "no downcast function registered",
+            })
         };
 
         let arc = downcast_fn.clone()
@@ -142,7 +168,11 @@ impl Type {
             .unwrap();
 
         let Ok(bean) = (arc.as_ref())(dyn_bean) else {
-            return Err(Error::from(format!("Failed to downcast {} to {}", type_ref.value().name(), type_name::<T>())))
+            return Err(Error::DowncastFailed {
+                from: type_ref.value().name().to_string(),
+                to: type_name::<T>().to_string(),
+                reason: "downcast function rejected the bean",
+            })
         };
 
         Ok(bean)
@@ -176,6 +206,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_question_mark)]
     fn should_be_thread_safe() {
         let ty = Type::of::<TestBean>();
         ty.add_downcast::<TestBean>(|b| { Ok(Arc::downcast::<TestBean>(b)?)});
@@ -188,4 +219,18 @@ mod tests {
 
         assert_eq!(test_trait.name(), test_bean.name);
     }
+
+    struct TestProxy { inner: DynBean }
+
+    #[test]
+    fn should_delegate_unregistered_downcasts() {
+        let ty = Type::of::<TestBean>();
+        ty.add_downcast::<TestBean>(Arc::downcast::<TestBean>);
+        Type::of::<TestProxy>().add_delegate(|b| b.clone().downcast::<TestProxy>().unwrap().inner.clone());
+
+        let given_dyn_bean: DynBean = Arc::new(TestProxy { inner: Arc::new(TestBean { name: "proxied_bean" }) });
+
+        let test_bean = Type::downcast::<TestBean>(given_dyn_bean).unwrap();
+        assert_eq!(test_bean.name, "proxied_bean");
+    }
 }
@@ -1,10 +1,8 @@
 use log::trace;
 
 use crate::app::App;
-use crate::config::get_config_context;
-use crate::context::auto_register_context::get_auto_register_context;
+use crate::context::module::Module;
 use crate::core::Error;
-use crate::logger::init_logger;
 
 pub mod core;
 pub mod context;
@@ -12,22 +10,14 @@ pub mod app;
 pub mod logger;
 pub mod config;
 
+/// Creates the App from `app.yaml`/`app.yml` and every auto-registered setup.
 pub fn create_app() -> Result<App, Error> {
-    let context = get_config_context(vec![
-        "app.yaml".to_string(),
-        "app.yml".to_string(),
-    ])?;
-    init_logger(&context)?;
-
     trace!("setup - create default App instance");
-    let app = App::default();
-
-    trace!("setup - adding {} to App", &context);
-    app.add_context(context);
-
-    let context = get_auto_register_context()?;
-    trace!("setup - adding {} to App", &context);
-    app.add_context(context);
-
-    Ok(app)
+    App::builder()
+        .config_files(vec![
+            "app.yaml".to_string(),
+            "app.yml".to_string(),
+        ])
+        .module(Module::auto_registered())
+        .build()
 }
@@ -1,3 +1,5 @@
+use std::sync::Once;
+
 use log4rs::config::RawConfig;
 use serde::Deserialize;
 use crate::context::context::Context;
@@ -20,9 +22,24 @@ const DEFAULT_LOGGING_CONFIG: &str = r#"
 }
 "#;
 
+static LOGGER_INITIALIZED: Once = Once::new();
+
+/// Initializes log4rs from the `logging` property. Only the first call configures the logger,
+/// later ones, e.g. from several apps created within a test binary, do nothing.
 pub fn init_logger(config: &Context) -> Result<(), Error> {
-    let config = config.get_bean::<config::Config>("config")
-        .map_err(|e| Error::from(format!("Failed to get config bean for logger initialization: {}", e)))?;
+    let mut result = None;
+    LOGGER_INITIALIZED.call_once(|| result = Some(configure_logger(config)));
+    match result {
+        Some(result) => result,
+        None => {
+            log::debug!("Logger is already initialized");
+            Ok(())
+        },
+    }
+}
+
+fn configure_logger(config: &Context) -> Result<(), Error> {
+    let config = config.get_bean::<config::Config>("config")?;
 
     let raw_config_value = config.get::<serde_json::Value>("logging")
         .unwrap_or_else(|e| {
@@ -41,4 +58,4 @@ pub fn init_logger(config: &Context) -> Result<(), Error> {
     log4rs::init_raw_config(raw_config).map_err(|e| {
         Error::from(format!("Failed to initialize log4rs logger: {}", e))
     })
-}
\ No newline at end of file
+}
@@ -3,33 +3,37 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::{FnArg, GenericArgument, ItemFn, LitStr, parse_macro_input, parse_quote, PathArguments, PatType, ReturnType, Signature, Type};
+use crate::inject::bean_attrs::BeanAttrs;
 use crate::inject::bean_field::BeanField;
-use crate::inject::generate_value_based_on_config;
+use crate::inject::into_vine_error;
 
-pub fn generate_setup_fn_for_bean(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn generate_setup_fn_for_bean(attr: TokenStream, input: TokenStream) -> TokenStream {
     let ItemFn {
+        attrs,
         vis,
-        sig: Signature { output, ident, inputs, .. },
+        sig: Signature { asyncness, output, ident, inputs, .. },
         block,
-        ..
     } = parse_macro_input!(input as ItemFn);
 
+    let bean_attrs = match BeanAttrs::parse(&attrs).and_then(|mut bean_attrs| {
+        bean_attrs.parse_bean_args(attr)?;
+        Ok(bean_attrs)
+    }) {
+        Ok(bean_attrs) => bean_attrs.builder_calls(),
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let attrs: Vec<_> = attrs.iter().filter(|attr| !BeanAttrs::is_bean_attr(attr)).collect();
+
     let args: Vec<_> = inputs.iter().map(|arg| {
         let FnArg::Typed(PatType { pat, ty, .. }) = arg else { panic!("unsupported FnArg"); };
-        return quote!(#pat: #ty)
+        quote!(#pat: #ty)
     }).collect();
 
-    let resolved_fn_args: Vec<_> = inputs.iter().map(|fn_arg| match parse_quote!(#fn_arg) {
-        BeanField::Bean(_, ty, name) => quote!(ctx.get_bean::<#ty>(#name)?,),
-        BeanField::PrimaryBean(_, ty) => quote!(ctx.get_primary_bean::<#ty>()?,),
-        BeanField::Beans(_, ty) => quote!(ctx.get_beans::<#ty>()?,),
-        BeanField::Value(_, ty, value) => {
-            let compute_call = generate_value_based_on_config(&ty, &value);
-            quote!({
-                let config = ctx.get_bean::<dyn vine::vine_core::config::PropertyResolver + Send + Sync>("config")?;
-                #compute_call
-            },)
-        },
+    let bean_fields: Vec<BeanField> = inputs.iter().map(|fn_arg| parse_quote!(#fn_arg)).collect();
+    let dependencies: Vec<_> = bean_fields.iter().filter_map(BeanField::dependency).collect();
+    let resolved_fn_args: Vec<_> = bean_fields.iter().map(|fn_arg| {
+        let resolved = fn_arg.resolve();
+        quote!(#resolved,)
     }).collect();
 
     let fn_name_str = ident.to_string();
@@ -38,34 +42,84 @@ pub fn generate_setup_fn_for_bean(_attr: TokenStream, input: TokenStream) -> Tok
     let setup_ident = format!("SETUP_{}", fn_name_str.to_uppercase());
     let setup_ident = Ident::new(&setup_ident, Span::call_site());
 
-    let ty = get_create_fn_output(&output);
+    let (ty, fallible) = match get_create_fn_output(&output) {
+        Ok(output) => output,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let into_vine_error = into_vine_error();
+    let map_err = match fallible {
+        true => quote!(.map_err(#into_vine_error)?),
+        false => quote!(),
+    };
+    // async factories get their declared dependencies resolved up front by Context::get_bean_async
+    let get_fn = match asyncness {
+        None => quote!(.get(std::sync::Arc::new(|ctx| {
+            let bean: vine::vine_core::core::DynBean = #ident(#(#resolved_fn_args)*)#map_err;
+            Ok(bean)
+        }))),
+        Some(_) => quote!(.get_async(|ctx| async move {
+            let ctx = &ctx;
+            let bean: vine::vine_core::core::DynBean = #ident(#(#resolved_fn_args)*).await #map_err;
+            Ok(bean)
+        })),
+    };
+    // the type registering the bean lives in the type namespace, next to the fn of the same name
     let extended = quote!(
-        #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
-        pub static #setup_ident: fn(&vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> = |ctx| {
-            let ty = vine::vine_core::core::ty::Type::of::<#ty>();
-            ty.add_downcast::<#ty>(|b| Ok(std::sync::Arc::downcast::<#ty>(b)?));
-
-            let bean_def = vine::vine_core::core::bean_def::BeanDef::builder()
-                .name(#bean_name)
-                .ty(ty)
-                .get(std::sync::Arc::new(|ctx| Ok(#ident(#(#resolved_fn_args)*))))
-                .build();
-            ctx.register(bean_def)
+        #[doc = concat!("Registers the bean created by [`", #fn_name_str, "()`], see `AppBuilder::register`.")]
+        #[allow(non_camel_case_types, dead_code)]
+        #vis struct #ident {}
+
+        impl vine::vine_core::context::registration::Register for #ident {
+            fn register(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                let ty = vine::vine_core::core::ty::Type::of::<#ty>();
+                ty.add_downcast::<#ty>(std::sync::Arc::downcast::<#ty>);
+
+                let bean_def = vine::vine_core::core::bean_def::BeanDef::builder()
+                    .name(#bean_name)
+                    .ty(ty)
+                    #bean_attrs
+                    #(.dependency(#dependencies))*
+                    #get_fn
+                    .build();
+                ctx.register(bean_def)
+            }
+        }
+
+        #[vine::distributed_slice(vine::vine_core::context::auto_register_context::NAMED_SETUP)]
+        pub static #setup_ident: vine::vine_core::context::auto_register_context::Setup = vine::vine_core::context::auto_register_context::Setup {
+            name: #bean_name,
+            setup: <#ident as vine::vine_core::context::registration::Register>::register,
         };
 
-        #vis fn #ident (#(#args),*) #output { #block }
+        #(#attrs)*
+        #vis #asyncness fn #ident (#(#args),*) #output #block
     );
 
     extended.into()
 }
 
-fn get_create_fn_output(return_type: &ReturnType) -> Type {
-    let ReturnType::Type(_, box_type) = return_type else { panic!() };
-    let Type::Path(path_type) =  box_type.as_ref() else { panic!() };
-    let Some(path_seg) = path_type.path.segments.first() else { panic!() };
+/// Returns `T` of `Arc<T>` or `Result<Arc<T>, E>`, and whether the factory is fallible.
+fn get_create_fn_output(return_type: &ReturnType) -> syn::Result<(Type, bool)> {
+    let ReturnType::Type(_, box_type) = return_type else {
+        return Err(syn::Error::new_spanned(return_type, "#[bean] function must return Arc<T> or Result<Arc<T>, E>"));
+    };
 
-    let PathArguments::AngleBracketed(x) = &path_seg.arguments else { panic!() };
-    let Some(GenericArgument::Type(class)) = x.args.first() else { panic!() };
+    let (ident, ty) = get_first_type_argument(box_type)?;
+    if ident != "Result" {
+        return Ok((ty.clone(), false));
+    }
 
-    class.clone()
-}
\ No newline at end of file
+    let (_, ty) = get_first_type_argument(ty)?;
+    Ok((ty.clone(), true))
+}
+
+fn get_first_type_argument(ty: &Type) -> syn::Result<(&Ident, &Type)> {
+    let error = || syn::Error::new_spanned(ty, "#[bean] function must return Arc<T> or Result<Arc<T>, E>");
+    let Type::Path(path_type) = ty else { return Err(error()) };
+    let Some(path_seg) = path_type.path.segments.last() else { return Err(error()) };
+
+    let PathArguments::AngleBracketed(x) = &path_seg.arguments else { return Err(error()) };
+    let Some(GenericArgument::Type(class)) = x.args.first() else { return Err(error()) };
+
+    Ok((&path_seg.ident, class))
+}
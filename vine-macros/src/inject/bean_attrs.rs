@@ -0,0 +1,129 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Expr, LitStr, Result, Token, Type, TypeParamBound};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+
+const BEAN_ATTRS: [&str; 8] = ["primary", "lazy", "order", "scope", "profile", "conditional_on_property", "conditional_on_bean", "conditional_on_missing_bean"];
+
+/// Bean level attributes shared by `#[derive(Bean)]` structs and `#[bean]` functions.
+pub struct BeanAttrs {
+    primary: bool,
+    lazy: bool,
+    order: Option<Expr>,
+    scope: Option<LitStr>,
+    conditions: Vec<TokenStream>,
+}
+
+struct PropertyCondition {
+    name: LitStr,
+    having: Option<LitStr>,
+}
+
+impl Parse for PropertyCondition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<LitStr>()?;
+        let mut having = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            let key = input.parse::<syn::Ident>()?;
+            if key != "having" {
+                return Err(syn::Error::new(key.span(), "expected `having = \"value\"`"));
+            }
+            input.parse::<Token![=]>()?;
+            having = Some(input.parse::<LitStr>()?);
+        }
+        Ok(PropertyCondition { name, having })
+    }
+}
+
+impl BeanAttrs {
+    pub fn is_bean_attr(attr: &Attribute) -> bool {
+        BEAN_ATTRS.iter().any(|name| attr.path().is_ident(name))
+    }
+
+    pub fn parse(attrs: &[Attribute]) -> Result<BeanAttrs> {
+        let condition = quote!(vine::vine_core::core::condition::Condition);
+        let mut bean_attrs = BeanAttrs { primary: false, lazy: false, order: None, scope: None, conditions: Vec::new() };
+
+        for attr in attrs {
+            if attr.path().is_ident("primary") {
+                bean_attrs.primary = true;
+            } else if attr.path().is_ident("lazy") {
+                bean_attrs.lazy = true;
+            } else if attr.path().is_ident("order") {
+                bean_attrs.order = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("scope") {
+                bean_attrs.scope = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("profile") {
+                let profiles = attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+                let profiles = profiles.iter();
+                bean_attrs.conditions.push(quote!(#condition::on_profile(&[#(#profiles),*])));
+            } else if attr.path().is_ident("conditional_on_property") {
+                let PropertyCondition { name, having } = attr.parse_args()?;
+                let having = match having {
+                    None => quote!(None),
+                    Some(having) => quote!(Some(#having)),
+                };
+                bean_attrs.conditions.push(quote!(#condition::on_property(#name, #having)));
+            } else if attr.path().is_ident("conditional_on_bean") {
+                let ty = with_send_sync(attr.parse_args()?);
+                bean_attrs.conditions.push(quote!(#condition::on_bean::<#ty>()));
+            } else if attr.path().is_ident("conditional_on_missing_bean") {
+                let ty = with_send_sync(attr.parse_args()?);
+                bean_attrs.conditions.push(quote!(#condition::on_missing_bean::<#ty>()));
+            }
+        }
+
+        Ok(bean_attrs)
+    }
+
+    /// Parses `#[bean(...)]` arguments, e.g. `#[bean(scope = "prototype", order = 10)]` or `#[bean(lazy)]`.
+    pub fn parse_bean_args(&mut self, args: proc_macro::TokenStream) -> Result<()> {
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("lazy") {
+                self.lazy = true;
+                Ok(())
+            } else if meta.path.is_ident("order") {
+                self.order = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("scope") {
+                self.scope = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported bean property"))
+            }
+        });
+        syn::parse::Parser::parse(parser, args)
+    }
+
+    /// Generates BeanDefBuilder calls for the parsed attributes.
+    pub fn builder_calls(&self) -> TokenStream {
+        let primary = self.primary;
+        let lazy = self.lazy;
+        let order = self.order.iter();
+        let scope = self.scope.iter();
+        let conditions = &self.conditions;
+        quote!(
+            .primary(#primary)
+            .lazy(#lazy)
+            #(.order(#order))*
+            #(.scope(#scope))*
+            #(.condition(#conditions))*
+        )
+    }
+}
+
+/// Trait objects are registered as `dyn Trait + Send + Sync`, so `dyn Trait` is extended the same way.
+fn with_send_sync(ty: Type) -> Type {
+    let Type::TraitObject(mut trait_object) = ty else { return ty };
+    let has_send = trait_object.bounds.iter().any(|bound| match bound {
+        TypeParamBound::Trait(bound) => bound.path.is_ident("Send"),
+        _ => false,
+    });
+
+    if !has_send {
+        trait_object.bounds.push(syn::parse_quote!(Send));
+        trait_object.bounds.push(syn::parse_quote!(Sync));
+    }
+    Type::TraitObject(trait_object)
+}
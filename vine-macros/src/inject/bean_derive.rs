@@ -1,52 +1,128 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{Data, DataStruct, DeriveInput, Ident, LitStr, parse_macro_input, parse_quote};
+use quote::format_ident;
+use syn::{Data, DataStruct, DeriveInput, Error, Fields, Ident, LitStr, parse_macro_input};
 
+use crate::inject::{ident_part, parse_bean_instances, setup_name, with_bean_bounds};
+use crate::inject::bean_attrs::BeanAttrs;
 use crate::inject::bean_field::BeanField;
-use crate::inject::generate_value_based_on_config;
 
 pub fn generate_setup_fn_for_bean_derive(input: TokenStream) -> TokenStream {
     let DeriveInput {
+        attrs,
         ident,
+        generics,
         data: Data::Struct(DataStruct { fields, .. }),
         ..
     } = parse_macro_input!(input) else { panic!("") };
 
-    let resolved_fields: Vec<_> = fields.iter().map(|field| match parse_quote!(#field) {
-        BeanField::Bean(field, ty, name) => quote!(#field: ctx.get_bean::<#ty>(#name)?,),
-        BeanField::PrimaryBean(field, ty) => quote!(#field: ctx.get_primary_bean::<#ty>()?,),
-        BeanField::Beans(field, ty) => quote!(#field: ctx.get_beans::<#ty>()?,),
-        BeanField::Value(field, ty, value) => {
-            let compute_call = generate_value_based_on_config(&ty, &value);
-            quote!(#field: {
-                let config = ctx.get_bean::<dyn vine::vine_core::config::PropertyResolver + Send + Sync>("config")?;
-                #compute_call
-            },)
-        },
-    }).collect();
-
-    let setup_ident = format!("SETUP_{}", &ident.to_string().to_uppercase());
-    let setup_ident = Ident::new(&setup_ident, Span::call_site());
+    let bean_attrs = match BeanAttrs::parse(&attrs) {
+        Ok(bean_attrs) => bean_attrs.builder_calls(),
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let instances = match parse_bean_instances(&attrs) {
+        Ok(instances) => instances,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let bean_name = &ident.to_string();
-    let bean_name = LitStr::new(&bean_name, Span::call_site());
+    // fields of tuple structs are named after their position, e.g. `_0`
+    let bean_fields: syn::Result<Vec<BeanField>> = fields.iter().enumerate().map(|(position, field)| {
+        let mut field = field.clone();
+        field.ident.get_or_insert_with(|| format_ident!("_{}", position));
+        syn::parse2(quote!(#field))
+    }).collect();
+    let bean_fields = match bean_fields {
+        Ok(bean_fields) => bean_fields,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let output = quote! {
-        #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
-        pub static #setup_ident: fn(&vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> = |ctx| {
-            let ty = vine::vine_core::core::ty::Type::of::<#ident>();
-            ty.add_downcast::<#ident>(std::sync::Arc::downcast::<#ident>);
+    let dependencies: Vec<_> = bean_fields.iter().filter_map(BeanField::dependency).collect();
+    let resolved_fields: Vec<_> = bean_fields.iter().map(BeanField::resolve).collect();
+    let field_idents: Vec<_> = bean_fields.iter().map(BeanField::ident).collect();
+    // registers the bean of type `self_ty`, `Self` within the impl of a generic bean
+    let register = |self_ty: proc_macro2::TokenStream, bean_name: proc_macro2::TokenStream| {
+        let bean = match &fields {
+            Fields::Named(_) => quote!(#self_ty { #(#field_idents: #resolved_fields,)* }),
+            Fields::Unnamed(_) => quote!(#self_ty(#(#resolved_fields,)*)),
+            Fields::Unit => quote!(#self_ty),
+        };
+        quote!(
+            let ty = vine::vine_core::core::ty::Type::of::<#self_ty>();
+            ty.add_downcast::<#self_ty>(std::sync::Arc::downcast::<#self_ty>);
             let bean_def = vine::vine_core::core::bean_def::BeanDef::builder()
                 .name(#bean_name)
                 .ty(ty)
-                .get(std::sync::Arc::new(|ctx| Ok(std::sync::Arc::new(#ident {
-                    #(#resolved_fields)*
-                }))))
+                #bean_attrs
+                #(.dependency(#dependencies))*
+                .get(std::sync::Arc::new(|ctx| Ok(std::sync::Arc::new(#bean))))
                 .build();
             ctx.register(bean_def)
-        };
+        )
+    };
+
+    let output = match (generics.params.is_empty(), instances) {
+        (true, None) => {
+            let setup_ident = format!("SETUP_{}", &ident.to_string().to_uppercase());
+            let setup_ident = Ident::new(&setup_ident, Span::call_site());
+
+            let bean_name = &ident.to_string();
+            let bean_name = LitStr::new(bean_name, Span::call_site());
+            let register = register(quote!(#ident), quote!(#bean_name));
+
+            quote! {
+                impl vine::vine_core::context::registration::Register for #ident {
+                    fn register(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                        #register
+                    }
+                }
+
+                #[vine::distributed_slice(vine::vine_core::context::auto_register_context::NAMED_SETUP)]
+                pub static #setup_ident: vine::vine_core::context::auto_register_context::Setup = vine::vine_core::context::auto_register_context::Setup {
+                    name: #bean_name,
+                    setup: <#ident as vine::vine_core::context::registration::Register>::register,
+                };
+            }
+        },
+        (false, Some(instances)) => {
+            let generics = with_bean_bounds(&generics);
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            let register = register(quote!(Self), quote!(name));
+
+            let setups = instances.iter().map(|instance| {
+                let setup_ident = format!("SETUP_INSTANCE_{}", ident_part(instance).to_uppercase());
+                let setup_ident = Ident::new(&setup_ident, Span::call_site());
+                let bean_name = setup_name(instance);
+                quote! {
+                    impl vine::vine_core::context::registration::Register for #instance {
+                        fn register(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                            <#instance>::__vine_register_bean(ctx, #bean_name)
+                        }
+                    }
+
+                    #[vine::distributed_slice(vine::vine_core::context::auto_register_context::NAMED_SETUP)]
+                    pub static #setup_ident: vine::vine_core::context::auto_register_context::Setup = vine::vine_core::context::auto_register_context::Setup {
+                        name: #bean_name,
+                        setup: <#instance as vine::vine_core::context::registration::Register>::register,
+                    };
+                }
+            });
+
+            quote! {
+                impl #impl_generics #ident #ty_generics #where_clause {
+                    #[doc(hidden)]
+                    pub fn __vine_register_bean(ctx: &vine::vine_core::context::context::Context, name: &'static str) -> Result<(), vine::vine_core::core::Error> {
+                        #register
+                    }
+                }
+
+                #(#setups)*
+            }
+        },
+        (false, None) => Error::new_spanned(&generics, "generic beans must list their concrete types with #[bean_instances(...)]")
+            .to_compile_error(),
+        (true, Some(_)) => Error::new_spanned(&ident, "#[bean_instances(...)] is only supported on generic beans")
+            .to_compile_error(),
     };
     output.into()
 }
-
@@ -1,26 +1,131 @@
 use PathArguments::AngleBracketed;
-use syn::{AngleBracketedGenericArguments, Field, GenericArgument, Ident, LitStr, PathArguments, PathSegment, Result, Type};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{AngleBracketedGenericArguments, Error, Expr, Field, GenericArgument, Ident, LitStr, PathArguments, PathSegment, Result, Type};
 use syn::parse::{Parse, ParseStream};
 
+use crate::inject::generate_value_based_on_config;
+
 pub enum BeanField {
     Bean(Ident, Type, LitStr),
     PrimaryBean(Ident, Type),
     Beans(Ident, Type),
-    Value(Ident, Type, LitStr)
+    Value(Ident, Type, LitStr),
+    Lazy(Ident, Type, Option<LitStr>),
+    Provider(Ident, Type, Option<LitStr>),
+    OptionalBean(Ident, Type, Option<LitStr>),
+    BeanMap(Ident, Type),
+    Marker(Ident),
+    Default(Ident, Type),
+    Init(Ident, Expr),
 }
 
-fn get_inner_type(ty: &Type) -> (&Ident, &Type) {
+const FIELD_ATTRS: [&str; 4] = ["qualifier", "value", "default", "init"];
+
+const UNSUPPORTED_FIELD: &str = "Unsupported field type. Expected variants are Vec<Arc<T>>, HashMap<String, Arc<T>>, Arc<T>, \
+    Option<Arc<T>>, Lazy<T>, Provider<T>, PhantomData<T>, or a field with #[qualifier], #[value], #[default] or #[init(expr)] attribute";
+
+impl BeanField {
+    /// Generates the Dependency recorded on the BeanDef, values are not bean dependencies.
+    pub fn dependency(&self) -> Option<TokenStream> {
+        let dependency = quote!(vine::vine_core::core::dependency::Dependency);
+        match self {
+            BeanField::Bean(field, ty, name) => {
+                let field = field.to_string();
+                Some(quote!(#dependency::qualified::<#ty>(#field, #name)))
+            },
+            BeanField::PrimaryBean(field, ty) => {
+                let field = field.to_string();
+                Some(quote!(#dependency::primary::<#ty>(#field)))
+            },
+            BeanField::Beans(field, ty) => {
+                let field = field.to_string();
+                Some(quote!(#dependency::all::<#ty>(#field)))
+            },
+            BeanField::Lazy(field, ty, name) | BeanField::Provider(field, ty, name) => {
+                let field = field.to_string();
+                match name {
+                    None => Some(quote!(#dependency::primary::<#ty>(#field).deferred())),
+                    Some(name) => Some(quote!(#dependency::qualified::<#ty>(#field, #name).deferred())),
+                }
+            },
+            BeanField::OptionalBean(field, ty, name) => {
+                let field = field.to_string();
+                match name {
+                    None => Some(quote!(#dependency::primary::<#ty>(#field).optional())),
+                    Some(name) => Some(quote!(#dependency::qualified::<#ty>(#field, #name).optional())),
+                }
+            },
+            BeanField::BeanMap(field, ty) => {
+                let field = field.to_string();
+                Some(quote!(#dependency::all::<#ty>(#field)))
+            },
+            BeanField::Value(..) | BeanField::Marker(..) | BeanField::Default(..) | BeanField::Init(..) => None,
+        }
+    }
+
+    /// Name of the struct field or function argument.
+    pub fn ident(&self) -> &Ident {
+        match self {
+            BeanField::Bean(ident, ..) | BeanField::PrimaryBean(ident, ..) | BeanField::Beans(ident, ..)
+            | BeanField::Value(ident, ..) | BeanField::Lazy(ident, ..) | BeanField::Provider(ident, ..)
+            | BeanField::OptionalBean(ident, ..) | BeanField::BeanMap(ident, ..) | BeanField::Marker(ident)
+            | BeanField::Default(ident, ..) | BeanField::Init(ident, ..) => ident,
+        }
+    }
+
+    /// Generates the expression resolving the field from `ctx`.
+    pub fn resolve(&self) -> TokenStream {
+        let provider = quote!(vine::vine_core::context::provider);
+        match self {
+            BeanField::Bean(_, ty, name) => quote!(ctx.get_bean::<#ty>(#name)?),
+            BeanField::PrimaryBean(_, ty) => quote!(ctx.get_primary_bean::<#ty>()?),
+            BeanField::Beans(_, ty) => quote!(ctx.get_beans::<#ty>()?),
+            BeanField::Value(_, ty, value) => {
+                let compute_call = generate_value_based_on_config(ty, value);
+                quote!({
+                    let config = ctx.get_bean::<dyn vine::vine_core::config::PropertyResolver + Send + Sync>("config")?;
+                    #compute_call
+                })
+            },
+            BeanField::Lazy(_, ty, None) => quote!(#provider::Lazy::<#ty>::primary(ctx)),
+            BeanField::Lazy(_, ty, Some(name)) => quote!(#provider::Lazy::<#ty>::named(ctx, #name)),
+            BeanField::Provider(_, ty, None) => quote!(#provider::Provider::<#ty>::primary(ctx)),
+            BeanField::Provider(_, ty, Some(name)) => quote!(#provider::Provider::<#ty>::named(ctx, #name)),
+            BeanField::OptionalBean(_, ty, None) => quote!(ctx.get_optional_primary_bean::<#ty>()?),
+            BeanField::OptionalBean(_, ty, Some(name)) => quote!(ctx.get_optional_bean::<#ty>(#name)?),
+            BeanField::BeanMap(_, ty) => quote!(ctx.get_beans_with_names::<#ty>()?.into_iter().collect()),
+            BeanField::Marker(_) => quote!(std::marker::PhantomData),
+            BeanField::Default(_, ty) => quote!(<#ty as Default>::default()),
+            BeanField::Init(_, init) => quote!(#init),
+        }
+    }
+}
+
+fn get_generic_args(ty: &Type) -> impl Iterator<Item = &GenericArgument> {
     let Type::Path(type_path) = ty else {
         panic!("Expected a type path")
     };
 
     let Some(PathSegment {
-                 ident, arguments: AngleBracketed( AngleBracketedGenericArguments { args, .. })
-             }) = type_path.path.segments.first() else {
+                 arguments: AngleBracketed( AngleBracketedGenericArguments { args, .. }), ..
+             }) = type_path.path.segments.last() else {
+        panic!("Expected a type with angle bracketed generic arguments")
+    };
+
+    args.iter()
+}
+
+fn get_inner_type(ty: &Type) -> (&Ident, &Type) {
+    let Type::Path(type_path) = ty else {
+        panic!("Expected a type path")
+    };
+
+    let Some(PathSegment { ident, .. }) = type_path.path.segments.last() else {
         panic!("Expected a type with angle bracketed generic arguments")
     };
 
-    let Some(GenericArgument::Type(inner_type)) = args.first() else {
+    let Some(GenericArgument::Type(inner_type)) = get_generic_args(ty).next() else {
         panic!("Expected at least one generic type argument")
     };
 
@@ -33,25 +138,89 @@ impl Parse for BeanField {
             ident: Some(ident), attrs, ty,
         .. } = input.call(Field::parse_named)? else { panic!("BeanField must be a named field with an identifier") };
 
-        if let Some(attr) = attrs.first() {
+        if let Some(attr) = attrs.iter().find(|attr| FIELD_ATTRS.iter().any(|name| attr.path().is_ident(name))) {
             if attr.path().is_ident("qualifier") {
                 let bean_name = attr.parse_args::<LitStr>()?;
-                let (_, ty) = get_inner_type(&ty);
-                return Ok(BeanField::Bean(ident, ty.clone(), bean_name));
+                let (ty_ident, ty) = get_inner_type(&ty);
+                return Ok(match ty_ident.to_string().as_str() {
+                    "Lazy" => BeanField::Lazy(ident, ty.clone(), Some(bean_name)),
+                    "Provider" => BeanField::Provider(ident, ty.clone(), Some(bean_name)),
+                    "Option" => BeanField::OptionalBean(ident, get_inner_type(ty).1.clone(), Some(bean_name)),
+                    _ => BeanField::Bean(ident, ty.clone(), bean_name),
+                });
             } else if attr.path().is_ident("value") {
                 let value_template = attr.parse_args::<LitStr>()?;
                 return Ok(BeanField::Value(ident, ty, value_template));
+            } else if attr.path().is_ident("default") {
+                attr.meta.require_path_only()?;
+                return Ok(BeanField::Default(ident, ty));
+            } else if attr.path().is_ident("init") {
+                return Ok(BeanField::Init(ident, attr.parse_args::<Expr>()?));
             }
         }
 
-        let (ty_ident, ty) = get_inner_type(&ty);
+        if !matches!(&ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| matches!(segment.arguments, AngleBracketed(_)))) {
+            return Err(Error::new_spanned(&ty, UNSUPPORTED_FIELD));
+        }
+
+        let field_ty = ty;
+        let (ty_ident, ty) = get_inner_type(&field_ty);
         if ty_ident.eq("Vec") {
             let (_, ty) = get_inner_type(ty);
             return Ok(BeanField::Beans(ident, ty.clone()));
         } else if ty_ident.eq("Arc") {
             return Ok(BeanField::PrimaryBean(ident, ty.clone()));
+        } else if ty_ident.eq("Lazy") {
+            return Ok(BeanField::Lazy(ident, ty.clone(), None));
+        } else if ty_ident.eq("Provider") {
+            return Ok(BeanField::Provider(ident, ty.clone(), None));
+        } else if ty_ident.eq("HashMap") {
+            // beans are keyed by their names
+            if !matches!(ty, Type::Path(key_type) if key_type.qself.is_none() && key_type.path.is_ident("String")) {
+                return Err(Error::new_spanned(ty, "Expected HashMap<String, Arc<T>>, beans are keyed by their String names"));
+            }
+            let Some(GenericArgument::Type(value_type)) = get_generic_args(&field_ty).nth(1) else {
+                return Err(Error::new_spanned(&field_ty, "Expected HashMap<String, Arc<T>>"));
+            };
+            let (_, ty) = get_inner_type(value_type);
+            return Ok(BeanField::BeanMap(ident, ty.clone()));
+        } else if ty_ident.eq("Option") {
+            let (_, ty) = get_inner_type(ty);
+            return Ok(BeanField::OptionalBean(ident, ty.clone(), None));
+        } else if ty_ident.eq("PhantomData") {
+            return Ok(BeanField::Marker(ident));
         }
 
-        panic!(r#"Unsupported field type. Expected variants are Vec<Arc<T>>, Arc<T>, or a field with #[qualifier] or #[value] attribute"#)
+        Err(Error::new_spanned(&field_ty, UNSUPPORTED_FIELD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use crate::inject::bean_field::BeanField;
+
+    #[test]
+    fn should_parse_bean_maps_keyed_by_names() {
+        let field = syn::parse2::<BeanField>(quote!(beans: HashMap<String, Arc<dyn TestTrait + Send + Sync>>));
+        assert!(matches!(field, Ok(BeanField::BeanMap(..))));
+
+        let Err(error) = syn::parse2::<BeanField>(quote!(beans: HashMap<u32, Arc<dyn TestTrait + Send + Sync>>)) else {
+            panic!("HashMap<u32, _> must be rejected");
+        };
+        assert!(error.to_string().contains("HashMap<String, Arc<T>>"));
+    }
+
+    #[test]
+    fn should_parse_init_fields() {
+        let field = syn::parse2::<BeanField>(quote!(#[default] counter: AtomicUsize));
+        assert!(matches!(field, Ok(BeanField::Default(..))));
+
+        let field = syn::parse2::<BeanField>(quote!(#[init(AtomicUsize::new(1))] counter: AtomicUsize));
+        assert!(matches!(field, Ok(BeanField::Init(..))));
+
+        let field = syn::parse2::<BeanField>(quote!(#[default(0)] counter: AtomicUsize));
+        assert!(field.is_err());
     }
 }
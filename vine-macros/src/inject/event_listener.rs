@@ -0,0 +1,174 @@
+use proc_macro::TokenStream;
+
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Attribute, Error, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, ReturnType, Type};
+
+use crate::inject::{ident_part, into_vine_error};
+
+pub fn generate_setup_fn_for_event_listener(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(Span::call_site(), "#[event_listener] does not take arguments").to_compile_error().into();
+    }
+
+    let mut item_impl = match syn::parse::<ItemImpl>(item.clone()) {
+        Ok(item_impl) => item_impl,
+        Err(error) => {
+            // methods are marked within an impl block which carries the attribute too, and which
+            // removes the markers before they are expanded on their own
+            let error = match syn::parse::<ImplItemFn>(item) {
+                Ok(method) => Error::new_spanned(&method.sig, "#[event_listener] methods require #[event_listener] on their impl block"),
+                Err(_) => error,
+            };
+            return error.to_compile_error().into();
+        },
+    };
+    if item_impl.trait_.is_some() || !item_impl.generics.params.is_empty() {
+        return Error::new_spanned(&item_impl.self_ty, "#[event_listener] expects an inherent impl of a non generic bean")
+            .to_compile_error()
+            .into();
+    }
+
+    let mut listeners: Vec<EventListeners> = Vec::new();
+    for item in &mut item_impl.items {
+        let ImplItem::Fn(method) = item else { continue };
+        let marker_count = method.attrs.len();
+        method.attrs.retain(|attr| !is_marker(attr));
+        if method.attrs.len() == marker_count {
+            continue;
+        }
+
+        let (event, call) = match parse_listener(method) {
+            Ok(listener) => listener,
+            Err(error) => return error.to_compile_error().into(),
+        };
+        let key = quote!(#event).to_string();
+        let index = match listeners.iter().position(|listeners| listeners.key == key) {
+            Some(index) => index,
+            None => {
+                listeners.push(EventListeners { key, event, sync_calls: Vec::new(), async_calls: Vec::new() });
+                listeners.len() - 1
+            },
+        };
+        match method.sig.asyncness {
+            None => listeners[index].sync_calls.push(call),
+            Some(_) => listeners[index].async_calls.push(call),
+        }
+    }
+
+    if listeners.is_empty() {
+        return Error::new_spanned(&item_impl.self_ty, "#[event_listener] expects methods marked with #[event_listener]")
+            .to_compile_error()
+            .into();
+    }
+
+    generate_listeners(&item_impl, &listeners).into()
+}
+
+/// The marked methods of a bean taking the same event type.
+struct EventListeners {
+    key: String,
+    event: Type,
+    sync_calls: Vec<proc_macro2::TokenStream>,
+    async_calls: Vec<proc_macro2::TokenStream>,
+}
+
+fn is_marker(attr: &Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == "event_listener")
+}
+
+/// Checks the signature of a listener method, e.g. `fn on_started(&self, event: &ApplicationStarted)`,
+/// and returns its event type with the expression calling it.
+fn parse_listener(method: &ImplItemFn) -> syn::Result<(Type, proc_macro2::TokenStream)> {
+    let sig = &method.sig;
+    let mut inputs = sig.inputs.iter();
+    if !matches!(inputs.next(), Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none()) {
+        return Err(Error::new_spanned(sig, "event listeners must take &self"));
+    }
+    let (Some(FnArg::Typed(event_arg)), None) = (inputs.next(), inputs.next()) else {
+        return Err(Error::new_spanned(&sig.inputs, "event listeners must take exactly one event argument"));
+    };
+    let Type::Reference(event_ref) = event_arg.ty.as_ref() else {
+        return Err(Error::new_spanned(&event_arg.ty, "event listeners must take the event by reference, e.g. `&MyEvent`"));
+    };
+
+    let method_ident = &sig.ident;
+    let call = match &sig.asyncness {
+        None => quote!(self.#method_ident(event)),
+        Some(_) => quote!(self.#method_ident(event).await),
+    };
+    let call = match &sig.output {
+        ReturnType::Default => quote!({ #call; Ok::<(), vine::vine_core::core::Error>(()) }),
+        ReturnType::Type(..) => {
+            let into_vine_error = into_vine_error();
+            quote!(#call.map_err(#into_vine_error))
+        },
+    };
+    Ok((event_ref.elem.as_ref().clone(), call))
+}
+
+/// Implements `EventListener<E>` and `AsyncEventListener<E>` once per event type, dispatching the
+/// event to every marked method, and `RegisterAs` of each of them, called by a SETUP fn.
+fn generate_listeners(item_impl: &ItemImpl, listeners: &[EventListeners]) -> proc_macro2::TokenStream {
+    let ty = item_impl.self_ty.as_ref();
+    let event_module = quote!(vine::vine_core::core::event);
+    let error = quote!(vine::vine_core::core::Error);
+
+    let mut listener_impls = Vec::new();
+    let mut listener_traits = Vec::new();
+    for EventListeners { event, sync_calls, async_calls, .. } in listeners {
+        if !sync_calls.is_empty() {
+            listener_traits.push(quote!(#event_module::EventListener<#event>));
+            listener_impls.push(quote!(
+                impl #event_module::EventListener<#event> for #ty {
+                    fn on_event(&self, event: &#event) -> Result<(), #error> {
+                        let mut errors = Vec::new();
+                        #(if let Err(error) = #sync_calls { errors.push(error); })*
+                        #event_module::into_result(errors)
+                    }
+                }
+            ));
+        }
+
+        if !async_calls.is_empty() {
+            listener_traits.push(quote!(#event_module::AsyncEventListener<#event>));
+            listener_impls.push(quote!(
+                #[vine::async_trait]
+                impl #event_module::AsyncEventListener<#event> for #ty {
+                    async fn on_event(&self, event: &#event) -> Result<(), #error> {
+                        let mut errors = Vec::new();
+                        #(if let Err(error) = #async_calls { errors.push(error); })*
+                        #event_module::into_result(errors)
+                    }
+                }
+            ));
+        }
+    }
+
+    let setup_fn_name = format!("SETUP_EVENT_LISTENERS_OF_{}", ident_part(ty)).to_uppercase();
+    let setup_fn = Ident::new(&setup_fn_name, Span::call_site());
+
+    let register_as: Vec<_> = listener_traits.iter()
+        .map(|listener_trait| quote!(vine::vine_core::context::registration::RegisterAs<dyn #listener_trait + Send + Sync>))
+        .collect();
+
+    quote!(
+        #(#listener_impls)*
+
+        #(impl #register_as for #ty {
+            fn register_as(_: &vine::vine_core::context::context::Context) -> Result<(), #error> {
+                let ty = vine::vine_core::core::ty::Type::of::<#ty>();
+                ty.add_downcast::<dyn #listener_traits + Send + Sync>(|b| Ok(std::sync::Arc::downcast::<#ty>(b)?));
+                Ok(())
+            }
+        })*
+
+        #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
+        pub static #setup_fn: vine::vine_core::context::auto_register_context::SetupFn = |ctx| {
+            #(<#ty as #register_as>::register_as(ctx)?;)*
+            Ok(())
+        };
+
+        #item_impl
+    )
+}
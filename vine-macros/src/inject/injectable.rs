@@ -1,27 +1,320 @@
 use proc_macro::TokenStream;
 
 use proc_macro2::Span;
-use quote::quote;
-use syn::{Ident, ItemImpl, parse_macro_input};
+use quote::{quote, ToTokens};
+use syn::{Attribute, Error, Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitInt, parse_macro_input, Pat, PatIdent, Path, ReturnType, Token, Type};
+use syn::punctuated::Punctuated;
 
-pub fn generate_setup_fn_for_injectable(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let item_impl = parse_macro_input!(item as ItemImpl);
+use crate::inject::{ident_part, with_bean_bounds};
+
+pub fn generate_setup_fn_for_injectable(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item_impl = parse_macro_input!(item as ItemImpl);
+
+    let InjectableArgs { intercept, instances } = match InjectableArgs::parse(attr) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    if let Some(attr) = item_impl.attrs.iter().find(|attr| attr.path().is_ident("bean_instances")) {
+        return Error::new_spanned(attr, "use #[injectable(instances(...))] to list the concrete types of a generic impl")
+            .to_compile_error()
+            .into();
+    }
+    // methods marked with #[timed], #[logged] or #[retry] are intercepted too
+    let method_interceptors = match take_method_interceptors(&mut item_impl) {
+        Ok(method_interceptors) => method_interceptors,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let intercept = intercept || method_interceptors.iter().any(|interceptors| !interceptors.is_empty());
     let Some((_, trait_ident, _)) = &item_impl.trait_ else { panic!("cannot resolve trait") };
     let ty = item_impl.self_ty.as_ref();
 
-    let setup_fn_name = format!("SETUP_{}_ALIAS_OF_{}", quote!(#trait_ident).to_string().to_uppercase(), quote!(#ty).to_string().to_uppercase());
-    let setup_fn = Ident::new(&setup_fn_name, Span::call_site());
+    let extended = match (item_impl.generics.params.is_empty(), instances) {
+        (true, None) => {
+            let setup_fn_name = format!("SETUP_{}_ALIAS_OF_{}", quote!(#trait_ident).to_string().to_uppercase(), quote!(#ty).to_string().to_uppercase());
+            let setup_fn = Ident::new(&setup_fn_name, Span::call_site());
+
+            let (proxy, register_proxy, ctx) = match intercept {
+                false => (quote!(), quote!(), quote!(_)),
+                true => match generate_proxy(&item_impl, trait_ident, ty, &method_interceptors) {
+                    Ok((proxy, register_proxy)) => (proxy, register_proxy, quote!(ctx)),
+                    Err(error) => return error.to_compile_error().into(),
+                },
+            };
+
+            let register_as = quote!(vine::vine_core::context::registration::RegisterAs<dyn #trait_ident + Send + Sync>);
+            quote!(
+                impl #register_as for #ty {
+                    fn register_as(#ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                        let ty = vine::vine_core::core::ty::Type::of::<#ty>();
+                        ty.add_downcast::<dyn #trait_ident + Send + Sync>(|b| Ok(std::sync::Arc::downcast::<#ty>(b)?));
+                        #register_proxy
+                        Ok(())
+                    }
+                }
+
+                #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
+                pub static #setup_fn: vine::vine_core::context::auto_register_context::SetupFn = <#ty as #register_as>::register_as;
+
+                #proxy
+
+                #item_impl
+            )
+        },
+        (false, Some(_)) if intercept => {
+            return Error::new_spanned(&item_impl.generics, "#[injectable(intercept)] does not support generic impls")
+                .to_compile_error()
+                .into();
+        },
+        (false, Some(instances)) => generate_generic_aliases(&item_impl, &instances),
+        (false, None) => {
+            return Error::new_spanned(&item_impl.generics, "generic impls must list their concrete types with #[injectable(instances(...))]")
+                .to_compile_error()
+                .into();
+        },
+        (true, Some(_)) => {
+            return Error::new_spanned(ty, "#[injectable(instances(...))] is only supported on generic impls")
+                .to_compile_error()
+                .into();
+        },
+    };
+
+    extended.into()
+}
+
+/// Registers the trait downcast of every concrete type of a generic impl. `RegisterAs` is
+/// implemented with the same generics, and called through a hidden helper trait so that the
+/// SETUP entries of the concrete types need not spell out the trait path, which may use the type
+/// params of the impl.
+fn generate_generic_aliases(item_impl: &ItemImpl, instances: &[Type]) -> proc_macro2::TokenStream {
+    let Some((_, trait_path, _)) = &item_impl.trait_ else { unreachable!() };
+    let ty = item_impl.self_ty.as_ref();
+
+    let helper = Ident::new(&format!("__Vine{}AliasOf{}", ident_part(trait_path), ident_part(ty)), Span::call_site());
+    let generics = with_bean_bounds(&item_impl.generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let register_as = quote!(vine::vine_core::context::registration::RegisterAs<dyn #trait_path + Send + Sync>);
+
+    let setups = instances.iter().map(|instance| {
+        let setup_fn_name = format!("SETUP_{}_ALIAS_OF_{}", ident_part(trait_path), ident_part(instance)).to_uppercase();
+        let setup_fn = Ident::new(&setup_fn_name, Span::call_site());
+        quote!(
+            #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
+            pub static #setup_fn: vine::vine_core::context::auto_register_context::SetupFn = <#instance as #helper>::__vine_register_as;
+        )
+    });
+
+    quote!(
+        impl #impl_generics #register_as for #ty #where_clause {
+            fn register_as(_: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                let ty = vine::vine_core::core::ty::Type::of::<Self>();
+                ty.add_downcast::<dyn #trait_path + Send + Sync>(|b| Ok(std::sync::Arc::downcast::<Self>(b)?));
+                Ok(())
+            }
+        }
 
-    let extended = quote!(
-        #[vine::distributed_slice(vine::vine_core::context::auto_register_context::SETUP)]
-        pub static #setup_fn: fn(&vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> = |_| {
-            let ty = vine::vine_core::core::ty::Type::of::<#ty>();
-            ty.add_downcast::<dyn #trait_ident + Send + Sync>(|b| Ok(std::sync::Arc::downcast::<#ty>(b)?));
-            Ok(())
-        };
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        trait #helper {
+            fn __vine_register_as(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error>;
+        }
+
+        impl #impl_generics #helper for #ty #where_clause {
+            fn __vine_register_as(ctx: &vine::vine_core::context::context::Context) -> Result<(), vine::vine_core::core::Error> {
+                <Self as #register_as>::register_as(ctx)
+            }
+        }
+
+        #(#setups)*
 
         #item_impl
+    )
+}
+
+/// Arguments of `#[injectable(intercept, instances(Repo<User>, Repo<Order>))]`, both optional.
+struct InjectableArgs {
+    intercept: bool,
+    instances: Option<Vec<Type>>,
+}
+
+impl InjectableArgs {
+    fn parse(attr: TokenStream) -> syn::Result<InjectableArgs> {
+        let mut args = InjectableArgs { intercept: false, instances: None };
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("intercept") {
+                args.intercept = true;
+                Ok(())
+            } else if meta.path.is_ident("instances") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let instances = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                if instances.is_empty() {
+                    return Err(meta.error("instances(...) expects at least one type"));
+                }
+                args.instances = Some(instances.into_iter().collect());
+                Ok(())
+            } else {
+                Err(meta.error("expected `intercept` or `instances(...)`"))
+            }
+        });
+        syn::parse::Parser::parse(parser, attr)?;
+        Ok(args)
+    }
+}
+
+/// Removes the `#[timed]`, `#[logged]` and `#[retry]` markers of the methods, and returns the
+/// interceptors they stand for, per method in the order of the impl items.
+fn take_method_interceptors(item_impl: &mut ItemImpl) -> syn::Result<Vec<Vec<proc_macro2::TokenStream>>> {
+    let interceptor = quote!(vine::vine_core::core::interceptor);
+    let mut method_interceptors = Vec::new();
+    for item in &mut item_impl.items {
+        let ImplItem::Fn(method) = item else { continue };
+        let mut interceptors = Vec::new();
+        let mut error = None;
+        method.attrs.retain(|attr| {
+            let Some(marker) = marker(attr) else { return true };
+            match marker.as_str() {
+                "timed" => interceptors.push(quote!(&#interceptor::Timed)),
+                "logged" => interceptors.push(quote!(&#interceptor::Logged)),
+                _ => match parse_retry_attempts(attr, &method.sig.output) {
+                    Ok(attempts) => interceptors.push(quote!(&#interceptor::Retry { attempts: #attempts })),
+                    Err(retry_error) => error = Some(retry_error),
+                },
+            }
+            false
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+        method_interceptors.push(interceptors);
+    }
+    Ok(method_interceptors)
+}
+
+fn marker(attr: &Attribute) -> Option<String> {
+    let ident = attr.path().segments.last()?.ident.to_string();
+    matches!(ident.as_str(), "timed" | "logged" | "retry").then_some(ident)
+}
+
+/// Parses `#[retry]` or `#[retry(attempts = 5)]`, the method is called 3 times by default.
+fn parse_retry_attempts(attr: &Attribute, output: &ReturnType) -> syn::Result<Expr> {
+    if !matches!(output, ReturnType::Type(_, output) if is_result(output)) {
+        return Err(Error::new_spanned(attr, "#[retry] requires a method returning a Result"));
+    }
+
+    let mut attempts = None;
+    if !matches!(attr.meta, syn::Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("attempts") {
+                attempts = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `attempts`"))
+            }
+        })?;
+    }
+    Ok(attempts.unwrap_or_else(|| Expr::Lit(syn::ExprLit { attrs: vec![], lit: LitInt::new("3", Span::call_site()).into() })))
+}
+
+/// Generates the proxy struct implementing the trait by running the `MethodInterceptor`s around
+/// each method of the intercepted bean, and the statements registering it within the SETUP fn.
+fn generate_proxy(
+    item_impl: &ItemImpl,
+    trait_path: &Path,
+    ty: &Type,
+    method_interceptors: &[Vec<proc_macro2::TokenStream>],
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let proxy = Ident::new(
+        &format!("__Vine{}ProxyOf{}", ident_part(trait_path), ident_part(ty)),
+        Span::call_site(),
     );
+    let trait_name = trait_path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
 
-    extended.into()
-}
\ No newline at end of file
+    let mut items = Vec::new();
+    let mut method_interceptors = method_interceptors.iter();
+    for item in &item_impl.items {
+        match item {
+            ImplItem::Fn(method) => {
+                let interceptors = method_interceptors.next().map(Vec::as_slice).unwrap_or_default();
+                items.push(generate_proxy_method(method, trait_path, ty, interceptors)?);
+            },
+            other => items.push(other.to_token_stream()),
+        }
+    }
+
+    let interceptor = quote!(vine::vine_core::core::interceptor);
+    let proxy_def = quote!(
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #proxy {
+            inner: vine::vine_core::core::DynBean,
+            target: std::sync::Arc<#ty>,
+            interception: #interceptor::Interception,
+        }
+
+        impl #trait_path for #proxy {
+            #(#items)*
+        }
+    );
+
+    let register_proxy = quote!(
+        let proxy_ty = vine::vine_core::core::ty::Type::of::<#proxy>();
+        proxy_ty.add_downcast::<dyn #trait_path + Send + Sync>(|b| Ok(std::sync::Arc::downcast::<#proxy>(b)?));
+        proxy_ty.add_delegate(|b| b.downcast_ref::<#proxy>().expect(stringify!(#proxy)).inner.clone());
+        #interceptor::register_proxy::<#ty, #proxy>(ctx, |bean, name, interceptors| {
+            let target = vine::vine_core::core::ty::Type::downcast::<#ty>(bean.clone())?;
+            let interception = #interceptor::Interception::new(name, #trait_name, interceptors.clone());
+            Ok(std::sync::Arc::new(#proxy { inner: bean, target, interception }))
+        })?;
+    );
+
+    Ok((proxy_def, register_proxy))
+}
+
+fn generate_proxy_method(method: &ImplItemFn, trait_path: &Path, ty: &Type, interceptors: &[proc_macro2::TokenStream]) -> syn::Result<proc_macro2::TokenStream> {
+    let mut sig = method.sig.clone();
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new_spanned(asyncness, "#[injectable(intercept)] does not support async methods"));
+    }
+    if !matches!(sig.inputs.first(), Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none()) {
+        return Err(Error::new_spanned(&sig, "#[injectable(intercept)] requires methods taking &self"));
+    }
+
+    // arguments are renamed as their patterns may not be plain idents, owned ones are cloned on
+    // each call as interceptors may proceed more than once
+    let mut args = Vec::new();
+    let mut call_args = Vec::new();
+    for (position, input) in sig.inputs.iter_mut().skip(1).enumerate() {
+        let FnArg::Typed(pat_type) = input else { continue };
+        let arg = Ident::new(&format!("arg{}", position), Span::call_site());
+        call_args.push(match pat_type.ty.as_ref() {
+            Type::Reference(reference) if reference.mutability.is_some() => {
+                return Err(Error::new_spanned(reference, "#[injectable(intercept)] does not support &mut arguments"));
+            },
+            Type::Reference(_) => quote!(#arg),
+            _ => quote!(#arg.clone()),
+        });
+        *pat_type.pat = Pat::Ident(PatIdent { attrs: vec![], by_ref: None, mutability: None, ident: arg.clone(), subpat: None });
+        args.push(arg);
+    }
+
+    let fallible = matches!(&sig.output, ReturnType::Type(_, output) if is_result(output));
+    let is_failed = if fallible { quote!(Result::is_err) } else { quote!(|_| false) };
+
+    let method_ident = &sig.ident;
+    Ok(quote!(
+        #sig {
+            self.interception.invoke(
+                stringify!(#method_ident),
+                &[#(&#args),*],
+                &[#(#interceptors),*],
+                || <#ty as #trait_path>::#method_ident(&*self.target, #(#call_args),*),
+                #is_failed,
+            )
+        }
+    ))
+}
+
+fn is_result(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else { return false };
+    type_path.path.segments.last().is_some_and(|segment| segment.ident == "Result")
+}
@@ -1,10 +1,13 @@
-use quote::quote;
-use syn::{LitStr, Type};
+use quote::{quote, ToTokens};
+use syn::{Attribute, Generics, GenericParam, LitStr, parse_quote, Token, Type};
+use syn::punctuated::Punctuated;
 
 pub mod bean;
 pub mod injectable;
+pub mod event_listener;
 pub mod bean_derive;
 pub mod bean_field;
+pub mod bean_attrs;
 
 fn generate_value_based_on_config(ty: &Type, value: &LitStr) -> proc_macro2::TokenStream {
     let Type::Path(type_path) = ty else {
@@ -30,6 +33,66 @@ fn generate_value_based_on_config(ty: &Type, value: &LitStr) -> proc_macro2::Tok
         "u64" => quote!(config.compute_template_value_as_u64(#value)?),
         "f32" => quote!(config.compute_template_value_as_f32(#value)?),
         "f64" => quote!(config.compute_template_value_as_f64(#value)?),
-        _ => quote!(config.compute_template_value(#value)?),
+        // any other FromStr type, failures are reported as PropertyParse errors
+        _ => quote!({
+            let value = config.compute_template_value(#value)?;
+            value.parse::<#ty>().map_err(|error| vine::vine_core::core::Error::PropertyParse {
+                value,
+                ty: stringify!(#ty),
+                source: Box::new(error),
+            })?
+        }),
     }
-}
\ No newline at end of file
+}
+
+/// Generates the closure converting the error of a fallible factory or listener into a vine
+/// Error, picking the conversion from the concrete error type, see `vine_core::core::factory`.
+fn into_vine_error() -> proc_macro2::TokenStream {
+    let factory = quote!(vine::vine_core::core::factory);
+    quote!(|error| {
+        use #factory::{DisplayIntoError as _, IntoError as _, StdErrorIntoError as _};
+        (&&&#factory::FactoryError::new(error)).into_vine_error()
+    })
+}
+
+/// Keeps the characters of the tokens which are valid in an ident, e.g. `a::B<C>` becomes `aBC`.
+fn ident_part(tokens: &impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_').collect()
+}
+
+/// Parses the concrete types listed by `#[bean_instances(Repo<User>, Repo<Order>)]`, if any.
+fn parse_bean_instances(attrs: &[Attribute]) -> syn::Result<Option<Vec<Type>>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("bean_instances")) else {
+        return Ok(None);
+    };
+
+    let instances = attr.parse_args_with(Punctuated::<Type, Token![,]>::parse_terminated)?;
+    if instances.is_empty() {
+        return Err(syn::Error::new_spanned(attr, "#[bean_instances(...)] expects at least one type"));
+    }
+    Ok(Some(instances.into_iter().collect()))
+}
+
+/// Adds the bounds required to register `Self` as a bean, i.e. `Self: Send + Sync + 'static` and
+/// `'static` type params, to the generics of an impl.
+fn with_bean_bounds(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<_> = generics.params.iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(type_param) => Some(type_param.ident.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates.push(parse_quote!(Self: Send + Sync + 'static));
+    for type_param in type_params {
+        where_clause.predicates.push(parse_quote!(#type_param: 'static));
+    }
+    generics
+}
+
+/// Name of the NAMED_SETUP entry registering the bean of the given type, e.g. `Repo<User>`.
+fn setup_name(ty: &Type) -> LitStr {
+    LitStr::new(&quote!(#ty).to_string().replace(' ', ""), proc_macro2::Span::call_site())
+}
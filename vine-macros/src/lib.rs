@@ -2,24 +2,122 @@ use proc_macro::TokenStream;
 
 use crate::inject::bean::generate_setup_fn_for_bean;
 use crate::inject::bean_derive::generate_setup_fn_for_bean_derive;
+use crate::inject::event_listener::generate_setup_fn_for_event_listener;
 use crate::inject::injectable::generate_setup_fn_for_injectable;
 
 mod inject;
 
-/// TODO: add documentation
+/// Registers the bean returned by the annotated function, named after the function, with its
+/// arguments injected like the fields of `#[derive(Bean)]`:
+/// ```ignore
+/// #[bean(scope = "prototype", order = 10)]
+/// #[conditional_on_property("db.enabled")]
+/// fn pool(#[value("${db.url}")] url: String, metrics: Arc<Metrics>) -> Result<Arc<Pool>, PoolError> {
+///     Pool::connect(&url, metrics)
+/// }
+/// ```
+/// The function returns either `Arc<T>` or `Result<Arc<T>, E>`, and may be `async`, in which case
+/// the bean must be created with `Context::get_bean_async` or on startup. A failing factory is
+/// reported as `Error::BeanCreation` carrying the bean name, its error `E` being converted with
+/// `From<E> for Error` when implemented, kept as the source of `Error::Factory` when it is a
+/// `std::error::Error`, or turned into `Error::Other` when it only implements `Display`.
+///
+/// The bean is configured by `#[bean(lazy, order = .., scope = "..")]` and by the `#[primary]`,
+/// `#[lazy]`, `#[order(..)]`, `#[scope("..")]`, `#[profile(..)]`, `#[conditional_on_property(..)]`,
+/// `#[conditional_on_bean(..)]` and `#[conditional_on_missing_bean(..)]` attributes.
 #[proc_macro_attribute]
 pub fn bean(attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_setup_fn_for_bean(attr, item)
 }
 
-/// TODO: add documentation
-#[proc_macro_derive(Bean, attributes(value, qualifier))]
+/// Registers the annotated struct as a bean named after it, with its fields injected.
+///
+/// Generic structs are registered once per concrete type listed by `#[bean_instances(...)]`,
+/// each bean being named after its type as written there, without spaces:
+/// ```ignore
+/// #[derive(Bean)]
+/// #[bean_instances(Repo<User>, Repo<Order>)]
+/// struct Repo<T: Entity> { pool: Arc<Pool>, entity: PhantomData<T> }
+///
+/// #[derive(Bean)]
+/// struct UserService { #[qualifier("Repo<User>")] users: Arc<Repo<User>> }
+/// ```
+/// `#[qualifier]` must match such names exactly, e.g. `"Repo<User>"` rather than `"Repo<User >"`
+/// or `"Repo<crate::User>"`.
+#[proc_macro_derive(Bean, attributes(bean_instances, value, qualifier, default, init, primary, lazy, order, scope, profile, conditional_on_property, conditional_on_bean, conditional_on_missing_bean))]
 pub fn bean_derive(input: TokenStream) -> TokenStream {
     generate_setup_fn_for_bean_derive(input)
 }
 
-/// TODO: add documentation
+/// Registers the annotated type as an implementation of the trait of the impl block.
+///
+/// With `#[injectable(intercept)]` the beans of the type are replaced by a proxy running the
+/// `MethodInterceptor` beans around each method call. Methods marked with [`macro@timed`],
+/// [`macro@logged`] or [`macro@retry`] are intercepted as well, their interceptors run first. The
+/// markers are consumed by `#[injectable]`, so they do not need to be imported:
+/// ```ignore
+/// #[injectable(intercept)]
+/// impl Repository for PgRepository {
+///     #[timed]
+///     #[retry(attempts = 5)]
+///     fn find(&self, id: u64) -> Result<User, Error> { ... }
+/// }
+/// ```
+/// Generic impls list the concrete types they are registered for, e.g.
+/// `#[injectable(instances(Repo<User>, Repo<Order>))] impl<T: Entity> Store for Repo<T>`.
+///
+/// Interceptors may proceed more than once, so the arguments of intercepted methods taken by
+/// value must implement `Clone`, and all of them must implement `Debug` to be passed to the
+/// interceptors. Async methods and `&mut` arguments are not supported.
 #[proc_macro_attribute]
 pub fn injectable(attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_setup_fn_for_injectable(attr, item)
-}
\ No newline at end of file
+}
+
+/// Logs the time taken by each call of a method of an `#[injectable]` impl block.
+#[proc_macro_attribute]
+pub fn timed(_: TokenStream, item: TokenStream) -> TokenStream {
+    method_interceptor_outside_injectable("#[timed]", item)
+}
+
+/// Logs each call of a method of an `#[injectable]` impl block, and whether it failed.
+#[proc_macro_attribute]
+pub fn logged(_: TokenStream, item: TokenStream) -> TokenStream {
+    method_interceptor_outside_injectable("#[logged]", item)
+}
+
+/// Calls a failing method of an `#[injectable]` impl block again, 3 times in total by default
+/// or as set by `#[retry(attempts = 5)]`. The method must return a `Result`.
+#[proc_macro_attribute]
+pub fn retry(_: TokenStream, item: TokenStream) -> TokenStream {
+    method_interceptor_outside_injectable("#[retry]", item)
+}
+
+// the markers are removed by #[injectable] on the impl block, they are only expanded on their own
+// when misplaced
+fn method_interceptor_outside_injectable(marker: &str, item: TokenStream) -> TokenStream {
+    let message = format!("{} methods require #[injectable] on their impl block", marker);
+    let mut error: proc_macro2::TokenStream = syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error();
+    error.extend(proc_macro2::TokenStream::from(item));
+    error.into()
+}
+
+/// Registers the methods of the annotated impl block which are marked with `#[event_listener]`
+/// as listeners of the event type they take by reference, other methods are left untouched:
+/// ```ignore
+/// #[event_listener]
+/// impl Metrics {
+///     #[event_listener]
+///     fn on_started(&self, event: &ApplicationStarted) { ... }
+///
+///     #[event_listener]
+///     async fn on_stopping(&self, event: &ApplicationStopping) -> Result<(), Error> { ... }
+/// }
+/// ```
+/// Several methods may listen to the same event, they are called in declaration order. Async
+/// methods are called after the sync ones, in the background by `ApplicationEventPublisher::publish`
+/// or awaited by `ApplicationEventPublisher::publish_async`.
+#[proc_macro_attribute]
+pub fn event_listener(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate_setup_fn_for_event_listener(attr, item)
+}
@@ -0,0 +1,206 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use vine_core::app::{App, AppBuilder};
+use vine_core::context::module::Module;
+use vine_core::context::override_policy::OverridePolicy;
+use vine_core::context::registration::{Register, RegisterAs};
+use vine_core::core::bean_def::BeanDef;
+use vine_core::core::Error;
+
+/// An [`App`] isolated from the process it runs in, created by [`TestApp::builder`].
+///
+/// Unlike `create_app`, the config only comes from the given files and inline properties, the
+/// logger is left untouched and the auto-registered setups may be filtered by name, so that many
+/// TestApps can live within a single test binary.
+pub struct TestApp {
+    app: App,
+}
+
+pub struct TestAppBuilder {
+    builder: AppBuilder,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    modules: Vec<Module>,
+}
+
+impl TestApp {
+    pub fn builder() -> TestAppBuilder {
+        TestAppBuilder {
+            builder: App::builder()
+                .environment(false)
+                .init_logger(false)
+                .override_policy(OverridePolicy::Replace),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            modules: Vec::new(),
+        }
+    }
+}
+
+impl Deref for TestApp {
+    type Target = App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+
+impl TestAppBuilder {
+    /// Sets an inline property, taking precedence over the config files.
+    pub fn property(mut self, key: &str, value: &str) -> Self {
+        self.builder = self.builder.property(key, value);
+        self
+    }
+
+    pub fn config_file(mut self, config_file: &str) -> Self {
+        self.builder = self.builder.config_file(config_file);
+        self
+    }
+
+    /// Registers only the included auto-registered beans, all of them when none is included.
+    /// Trait aliases and event listeners are registered regardless, as they add no bean.
+    pub fn include(mut self, name: &str) -> Self {
+        self.include.push(name.to_string());
+        self
+    }
+
+    pub fn exclude(mut self, name: &str) -> Self {
+        self.exclude.push(name.to_string());
+        self
+    }
+
+    /// Adds a module after the auto-registered setups, its beans replacing theirs.
+    pub fn module(mut self, module: Module) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    /// Registers `bean` as the `name` bean, replacing the auto-registered one if any.
+    ///
+    /// The bean is downcastable to `T`, trait downcasts of the mock type are registered as usual,
+    /// e.g. with `#[injectable]`.
+    pub fn mock<T: Send + Sync + 'static>(mut self, name: &str, bean: Arc<T>) -> Self {
+        self.builder = self.builder.register_instance(name, bean);
+        self
+    }
+
+    /// Registers the beans of `T`, replacing the auto-registered ones of the same name if any.
+    pub fn register<T: Register>(mut self) -> Self {
+        self.builder = self.builder.register::<T>();
+        self
+    }
+
+    /// Registers `T` as an implementation of `A`.
+    pub fn register_as<T: RegisterAs<A>, A: ?Sized>(mut self) -> Self {
+        self.builder = self.builder.register_as::<T, A>();
+        self
+    }
+
+    /// Registers a bean, replacing the auto-registered one of the same name if any.
+    pub fn register_bean_def(mut self, bean_def: impl Into<BeanDef>) -> Self {
+        self.builder = self.builder.register_bean_def(bean_def);
+        self
+    }
+
+    pub fn build(self) -> Result<TestApp, Error> {
+        let (include, exclude) = (self.include, self.exclude);
+        let auto_registered = Module::auto_registered_filtered(move |name| {
+            (include.is_empty() || include.iter().any(|included| included == name))
+                && !exclude.iter().any(|excluded| excluded == name)
+        });
+
+        let builder = self.modules.into_iter().fold(self.builder.module(auto_registered), AppBuilder::module);
+        Ok(TestApp { app: builder.build()? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use linkme::distributed_slice;
+    use vine_core::config::PropertyResolver;
+    use vine_core::context::auto_register_context::{NAMED_SETUP, Setup};
+    use vine_core::context::module::Module;
+    use vine_core::core::bean_def::BeanDef;
+    use vine_core::core::Error;
+    use vine_core::core::ty::Type;
+
+    use crate::TestApp;
+
+    struct Greeter { greeting: String }
+
+    #[distributed_slice(NAMED_SETUP)]
+    static SETUP_TEST_GREETER: Setup = Setup { name: "testGreeter", setup: |ctx| {
+        let ty = Type::of::<Greeter>();
+        ty.add_downcast::<Greeter>(Arc::downcast::<Greeter>);
+        ctx.register(BeanDef::builder()
+            .name("testGreeter")
+            .ty(ty)
+            .get(Arc::new(|ctx| {
+                let config = ctx.get_primary_bean::<dyn PropertyResolver + Send + Sync>()?;
+                let greeting = config.compute_template_value("${test.greeting:hello}")?;
+                Ok(Arc::new(Greeter { greeting }))
+            }))
+            .build())
+    } };
+
+    fn greeting(app: &TestApp) -> Result<String, Error> {
+        Ok(app.get_context().get_bean::<Greeter>("testGreeter")?.greeting.clone())
+    }
+
+    #[test]
+    fn should_use_inline_properties() -> Result<(), Error> {
+        let app = TestApp::builder().property("test.greeting", "hi").build()?;
+        assert_eq!(greeting(&app)?, "hi");
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_beans_by_mocks() -> Result<(), Error> {
+        let mock = Arc::new(Greeter { greeting: "mocked".to_string() });
+        let app = TestApp::builder().mock("testGreeter", mock).build()?;
+        assert_eq!(greeting(&app)?, "mocked");
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_beans_by_modules() -> Result<(), Error> {
+        let module = Module::new("greetings", |ctx| {
+            ctx.register(BeanDef::builder()
+                .name("testGreeter")
+                .ty(Type::of::<Greeter>())
+                .get(Arc::new(|_| Ok(Arc::new(Greeter { greeting: "from module".to_string() }))))
+                .build())
+        });
+        let app = TestApp::builder().module(module).build()?;
+        assert_eq!(greeting(&app)?, "from module");
+        Ok(())
+    }
+
+    #[test]
+    fn should_filter_setups_by_name() -> Result<(), Error> {
+        let app = TestApp::builder().exclude("testGreeter").build()?;
+        assert!(greeting(&app).is_err());
+
+        let app = TestApp::builder().include("testGreeter").build()?;
+        assert_eq!(greeting(&app)?, "hello");
+        assert!(!app.get_context().contains_bean_def("applicationEventPublisher"));
+        Ok(())
+    }
+
+    #[test]
+    fn should_run_independent_apps_in_parallel() {
+        let handles: Vec<_> = (0..8)
+            .map(|i| std::thread::spawn(move || {
+                let app = TestApp::builder().property("test.greeting", &format!("hi {}", i)).build()?;
+                greeting(&app)
+            }))
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap().unwrap(), format!("hi {}", i));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use vine::bean;
+use vine::vine_core::core::Error;
+use vine_test::TestApp;
+
+#[derive(Debug)]
+struct PoolError(String);
+
+impl Display for PoolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsupported database url {}", self.0)
+    }
+}
+
+impl StdError for PoolError {}
+
+struct Pool {
+    url: String,
+}
+
+#[bean]
+fn pool(#[value("${db.url}")] url: String) -> Result<Arc<Pool>, PoolError> {
+    match url.starts_with("postgres://") {
+        true => Ok(Arc::new(Pool { url })),
+        false => Err(PoolError(url)),
+    }
+}
+
+// converts into vine's Error
+struct MissingReplica;
+
+impl From<MissingReplica> for Error {
+    fn from(_: MissingReplica) -> Self {
+        Error::PropertyMissing { key: "db.replica".to_string() }
+    }
+}
+
+#[bean]
+fn replica() -> Result<Arc<Pool>, MissingReplica> {
+    Err(MissingReplica)
+}
+
+// only implements Display
+struct CacheFailure;
+
+impl Display for CacheFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cache is disabled")
+    }
+}
+
+#[bean]
+fn cache_pool() -> Result<Arc<Pool>, CacheFailure> {
+    Err(CacheFailure)
+}
+
+#[test]
+fn should_create_beans_of_fallible_factories() -> Result<(), Error> {
+    let app = TestApp::builder().property("db.url", "postgres://localhost").build()?;
+    let pool = app.get_context().get_bean::<Pool>("pool")?;
+    assert_eq!(pool.url, "postgres://localhost");
+    Ok(())
+}
+
+#[test]
+fn should_keep_errors_of_fallible_factories_as_sources() -> Result<(), Error> {
+    let app = TestApp::builder().property("db.url", "mysql://localhost").build()?;
+    let Err(error) = app.get_context().get_bean::<Pool>("pool") else {
+        panic!("the factory must fail");
+    };
+
+    assert!(matches!(error, Error::BeanCreation { ref name, .. } if name == "pool"));
+    let mut source = error.source();
+    while let Some(error) = source {
+        if let Some(pool_error) = error.downcast_ref::<PoolError>() {
+            assert_eq!(pool_error.0, "mysql://localhost");
+            return Ok(());
+        }
+        source = error.source();
+    }
+    panic!("PoolError is missing from the sources of {}", error);
+}
+
+#[test]
+fn should_convert_errors_of_fallible_factories_into_vine_errors() -> Result<(), Error> {
+    let app = TestApp::builder().property("db.url", "postgres://localhost").build()?;
+    let ctx = app.get_context();
+
+    let Err(Error::BeanCreation { source, .. }) = ctx.get_bean::<Pool>("replica") else { panic!("the factory must fail") };
+    assert!(matches!(source.as_ref(), Error::PropertyMissing { key } if key == "db.replica"));
+
+    let Err(Error::BeanCreation { source, .. }) = ctx.get_bean::<Pool>("cache_pool") else { panic!("the factory must fail") };
+    assert!(matches!(source.as_ref(), Error::Other(message) if message == "cache is disabled"));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::error::Error as StdError;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use vine::Bean;
+use vine::vine_core::core::Error;
+use vine_test::TestApp;
+
+#[derive(Bean)]
+struct Counter;
+
+#[derive(Bean)]
+struct Wrapper(Arc<Counter>, #[default] AtomicUsize);
+
+#[derive(Bean)]
+struct Settings {
+    #[default]
+    hits: AtomicUsize,
+    #[init(String::from("vine"))]
+    name: String,
+    #[value("${app.port}")]
+    port: u16,
+    counter: Arc<Counter>,
+}
+
+#[derive(Bean)]
+struct Limits {
+    #[value("${app.workers:4}")]
+    workers: usize,
+    #[value("${app.address:127.0.0.1:8080}")]
+    address: SocketAddr,
+}
+
+#[test]
+fn should_create_derived_beans() -> Result<(), Error> {
+    let app = TestApp::builder().property("app.port", "8080").build()?;
+    let ctx = app.get_context();
+
+    let counter = ctx.get_bean::<Counter>("Counter")?;
+
+    let wrapper = ctx.get_bean::<Wrapper>("Wrapper")?;
+    assert!(Arc::ptr_eq(&wrapper.0, &counter));
+    assert_eq!(wrapper.1.load(Ordering::SeqCst), 0);
+
+    let settings = ctx.get_bean::<Settings>("Settings")?;
+    assert_eq!(settings.hits.load(Ordering::SeqCst), 0);
+    assert_eq!(settings.name, "vine");
+    assert_eq!(settings.port, 8080);
+    assert!(Arc::ptr_eq(&settings.counter, &counter));
+    Ok(())
+}
+
+#[test]
+fn should_parse_values_of_any_from_str_type() -> Result<(), Error> {
+    let app = TestApp::builder().property("app.port", "8080").build()?;
+
+    let limits = app.get_context().get_bean::<Limits>("Limits")?;
+    assert_eq!(limits.workers, 4);
+    assert_eq!(limits.address, SocketAddr::from(([127, 0, 0, 1], 8080)));
+    Ok(())
+}
+
+#[test]
+fn should_report_values_failing_to_parse() -> Result<(), Error> {
+    let app = TestApp::builder().property("app.port", "8080").property("app.workers", "many").build()?;
+
+    let Err(error) = app.get_context().get_bean::<Limits>("Limits") else { panic!("expected error") };
+    let Error::BeanCreation { source, .. } = &error else { panic!("unexpected error: {}", error) };
+    let Error::PropertyParse { value, ty, .. } = source.as_ref() else { panic!("unexpected error: {}", error) };
+    assert_eq!((value.as_str(), *ty), ("many", "usize"));
+
+    let error: Box<dyn StdError + Send + Sync> = error.into();
+    let root_cause = error.source().and_then(StdError::source);
+    assert!(root_cause.is_some_and(|source| source.is::<std::num::ParseIntError>()));
+    Ok(())
+}
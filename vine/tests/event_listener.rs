@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+
+use vine::{Bean, event_listener};
+use vine::vine_core::core::Error;
+use vine::vine_core::core::event::ApplicationEventPublisher;
+use vine_test::TestApp;
+
+struct Greeted(&'static str);
+
+struct Farewell;
+
+#[derive(Bean)]
+struct GreetingListener {
+    #[init(Mutex::new(Vec::new()))]
+    calls: Mutex<Vec<String>>,
+}
+
+#[event_listener]
+impl GreetingListener {
+    #[event_listener]
+    fn record(&self, event: &Greeted) {
+        self.record_call(format!("record {}", event.0));
+    }
+
+    #[event_listener]
+    fn check(&self, event: &Greeted) -> Result<(), Error> {
+        self.record_call(format!("check {}", event.0));
+        match event.0 {
+            "fail" => Err(Error::from("check failed")),
+            _ => Ok(()),
+        }
+    }
+
+    #[event_listener]
+    async fn record_async(&self, event: &Greeted) {
+        self.record_call(format!("async {}", event.0));
+    }
+
+    #[vine::event_listener]
+    fn on_farewell(&self, _event: &Farewell) {
+        self.record_call("farewell".to_string());
+    }
+
+    fn record_call(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[tokio::test]
+async fn should_dispatch_events_to_marked_methods() -> Result<(), Error> {
+    let app = TestApp::builder().include("GreetingListener").build()?;
+    let publisher = ApplicationEventPublisher::new(app.get_context());
+
+    publisher.publish_async(Greeted("hello")).await?;
+    assert!(publisher.publish_async(Greeted("fail")).await.is_err());
+    publisher.publish_async(Farewell).await?;
+
+    let listener = app.get_context().get_bean::<GreetingListener>("GreetingListener")?;
+    assert_eq!(listener.calls(), vec![
+        "record hello", "check hello", "async hello",
+        "record fail", "check fail", "async fail",
+        "farewell",
+    ]);
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use vine::{Bean, injectable};
+use vine::vine_core::core::Error;
+use vine_test::TestApp;
+
+trait Entity {
+    const NAME: &'static str;
+}
+
+struct User;
+
+impl Entity for User {
+    const NAME: &'static str = "user";
+}
+
+struct Order;
+
+impl Entity for Order {
+    const NAME: &'static str = "order";
+}
+
+#[derive(Bean)]
+#[bean_instances(Repo<User>, Repo<Order>)]
+struct Repo<T: Entity> {
+    entity: PhantomData<T>,
+}
+
+trait Store {
+    fn entity(&self) -> &'static str;
+}
+
+#[injectable(instances(Repo<User>, Repo<Order>))]
+impl<T: Entity> Store for Repo<T> {
+    fn entity(&self) -> &'static str {
+        T::NAME
+    }
+}
+
+trait Finder<T> {
+    fn find(&self, id: u64) -> String;
+}
+
+#[injectable(instances(Repo<User>))]
+impl<T: Entity> Finder<T> for Repo<T> {
+    fn find(&self, id: u64) -> String {
+        format!("{} {}", T::NAME, id)
+    }
+}
+
+#[derive(Bean)]
+struct UserService {
+    #[qualifier("Repo<User>")]
+    users: Arc<Repo<User>>,
+    finder: Arc<dyn Finder<User> + Send + Sync>,
+    stores: Vec<Arc<dyn Store + Send + Sync>>,
+}
+
+#[test]
+fn should_register_generic_beans_per_instance() -> Result<(), Error> {
+    let app = TestApp::builder().build()?;
+    let ctx = app.get_context();
+
+    let service = ctx.get_bean::<UserService>("UserService")?;
+    assert_eq!(service.users.entity(), "user");
+    assert_eq!(service.finder.find(1), "user 1");
+
+    let mut entities: Vec<_> = service.stores.iter().map(|store| store.entity()).collect();
+    entities.sort();
+    assert_eq!(entities, vec!["order", "user"]);
+
+    assert_eq!(ctx.get_bean::<Repo<Order>>("Repo<Order>")?.entity(), "order");
+    assert!(ctx.get_bean::<Repo<Order>>("Repo<Order >").is_err());
+    Ok(())
+}
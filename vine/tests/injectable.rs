@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use vine::{Bean, injectable};
+use vine::vine_core::core::Error;
+use vine::vine_core::core::interceptor::{MethodInterceptor, MethodInvocation};
+use vine_test::TestApp;
+
+trait Repository {
+    fn find(&self, id: u64, name: String) -> Result<String, Error>;
+    fn count(&self) -> usize;
+}
+
+#[derive(Bean)]
+struct FlakyRepository {
+    #[default]
+    calls: AtomicUsize,
+}
+
+#[injectable(intercept)]
+impl Repository for FlakyRepository {
+    #[retry(attempts = 3)]
+    #[timed]
+    fn find(&self, id: u64, name: String) -> Result<String, Error> {
+        match self.calls.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(Error::from("connection reset")),
+            _ => Ok(format!("{} {}", id, name)),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Bean)]
+struct RecordingInterceptor {
+    #[init(Mutex::new(Vec::new()))]
+    calls: Mutex<Vec<String>>,
+}
+
+#[injectable]
+impl MethodInterceptor for RecordingInterceptor {
+    fn intercept(&self, invocation: &mut MethodInvocation) {
+        invocation.proceed();
+        self.calls.lock().unwrap().push(format!("{} failed={}", invocation, invocation.is_failed()));
+    }
+}
+
+#[test]
+fn should_intercept_methods_of_injectable_beans() -> Result<(), Error> {
+    let app = TestApp::builder().build()?;
+    let ctx = app.get_context();
+
+    let repository = ctx.get_bean::<dyn Repository + Send + Sync>("FlakyRepository")?;
+    assert_eq!(repository.find(1, "vine".to_string())?, "1 vine");
+    assert_eq!(repository.count(), 2);
+
+    let recording = ctx.get_bean::<RecordingInterceptor>("RecordingInterceptor")?;
+    assert_eq!(*recording.calls.lock().unwrap(), vec![
+        r#"Repository::find[1, "vine"] of FlakyRepository failed=true"#,
+        r#"Repository::find[1, "vine"] of FlakyRepository failed=false"#,
+        "Repository::count[] of FlakyRepository failed=false",
+    ]);
+
+    let bean: Arc<FlakyRepository> = ctx.get_bean("FlakyRepository")?;
+    assert_eq!(bean.count(), 2);
+    Ok(())
+}
+
+#[test]
+fn should_create_interceptors_requested_before_intercepted_beans() -> Result<(), Error> {
+    let app = TestApp::builder().build()?;
+    let ctx = app.get_context();
+
+    let recording = ctx.get_bean::<RecordingInterceptor>("RecordingInterceptor")?;
+    let repository = ctx.get_bean::<dyn Repository + Send + Sync>("FlakyRepository")?;
+    assert_eq!(repository.count(), 0);
+    assert_eq!(*recording.calls.lock().unwrap(), vec!["Repository::count[] of FlakyRepository failed=false"]);
+    Ok(())
+}
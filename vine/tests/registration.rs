@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use vine::{bean, Bean, injectable};
+use vine::vine_core::app::App;
+use vine::vine_core::context::module::Module;
+use vine::vine_core::core::Error;
+use vine_test::TestApp;
+
+trait Greeter {
+    fn greet(&self) -> String;
+}
+
+#[derive(Bean)]
+struct EnglishGreeter {
+    #[value("${greeting.name:world}")]
+    name: String,
+}
+
+#[injectable]
+impl Greeter for EnglishGreeter {
+    fn greet(&self) -> String {
+        format!("hello {}", self.name)
+    }
+}
+
+#[bean]
+fn punctuation() -> Arc<String> {
+    Arc::new("!".to_string())
+}
+
+struct Farewell;
+
+#[injectable]
+impl Greeter for Farewell {
+    fn greet(&self) -> String {
+        "goodbye".to_string()
+    }
+}
+
+#[bean]
+fn farewell() -> Arc<Farewell> {
+    Arc::new(Farewell)
+}
+
+#[test]
+fn should_register_beans_without_auto_registration() -> Result<(), Error> {
+    let app = App::builder()
+        .environment(false)
+        .init_logger(false)
+        .property("greeting.name", "vine")
+        .register::<EnglishGreeter>()
+        .register_as::<EnglishGreeter, dyn Greeter + Send + Sync>()
+        .build()?;
+
+    let greeter = app.get_context().get_primary_bean::<dyn Greeter + Send + Sync>()?;
+    assert_eq!(greeter.greet(), "hello vine");
+    assert!(app.get_context().get_bean::<String>("punctuation").is_err());
+    Ok(())
+}
+
+#[test]
+fn should_register_beans_of_modules() -> Result<(), Error> {
+    let module = Module::named("greetings")
+        .register::<EnglishGreeter>()
+        .register::<punctuation>();
+    let app = App::builder().environment(false).init_logger(false).module(module).build()?;
+
+    let greeter = app.get_context().get_bean::<EnglishGreeter>("EnglishGreeter")?;
+    assert_eq!(greeter.name, "world");
+    assert_eq!(*app.get_context().get_bean::<String>("punctuation")?, "!");
+    Ok(())
+}
+
+#[test]
+fn should_keep_trait_aliases_of_included_beans() -> Result<(), Error> {
+    let app = TestApp::builder().include("farewell").build()?;
+
+    let greeter = app.get_context().get_primary_bean::<dyn Greeter + Send + Sync>()?;
+    assert_eq!(greeter.greet(), "goodbye");
+    assert!(app.get_context().get_bean::<EnglishGreeter>("EnglishGreeter").is_err());
+    Ok(())
+}